@@ -0,0 +1,17 @@
+/// Way the code is executed. AST walks the expression tree directly, while VM compiles blocks to bytecode and executes them on a stack.
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Hash)]
+pub enum Backend {
+    AST,
+    VM
+}
+
+impl Backend {
+    pub fn get_for_name(name: &str) -> Option<Backend> {
+        match name {
+            "ast" => Some(Backend::AST),
+            "vm" => Some(Backend::VM),
+            _ => None
+        }
+    }
+}
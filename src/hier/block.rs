@@ -0,0 +1,31 @@
+use std::cell::OnceCell;
+use std::fmt::{Debug, Formatter};
+use std::rc::Rc;
+use crate::hier::compiler::{Chunk, Compiler};
+use crate::hier::expression::Expression;
+
+/// Block of expressions shared between values. Its contents are internal to the interpreter. It is compiled to a chunk on first use by the VM and the chunk is cached, so running the same block many times (for example, in a loop) doesn't copy or recompile it.
+#[derive(Clone)]
+pub struct Block {
+    pub(crate) expressions: Rc<Vec<Expression>>,
+    chunk: Rc<OnceCell<Rc<Chunk>>>
+}
+
+impl Block {
+    pub(crate) fn new(expressions: Vec<Expression>) -> Self {
+        Self {
+            expressions: Rc::new(expressions),
+            chunk: Rc::new(OnceCell::new())
+        }
+    }
+
+    pub(crate) fn chunk(&self) -> Rc<Chunk> {
+        self.chunk.get_or_init(|| Rc::new(Compiler::compile(&self.expressions))).clone()
+    }
+}
+
+impl Debug for Block {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.expressions)
+    }
+}
@@ -0,0 +1,188 @@
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Hash)]
+pub enum Builtin {
+    GET,
+    IMPORT,
+    LIST,
+    ADDITION,
+    SUBTRACTION,
+    MULTIPLICATION,
+    DIVISION,
+    NEGATE,
+    AND,
+    OR,
+    EQUAL,
+    NOT_EQUAL,
+    LESS_EQUAL,
+    GREATER_EQUAL,
+    LESS,
+    GREATER,
+    NULL_COALESCING,
+    APPEND,
+    BRPOINT,
+    MODULO,
+    INTEGER_DIVISION,
+    IS,
+    PRINT,
+    PRINTLN,
+    EVAL,
+    BREAK,
+    CONTINUE,
+    RETURN,
+    ERROR,
+    PANIC,
+    READ,
+    INSERT,
+    ROUND,
+    MAP,
+    REMOVE,
+    REPLACE,
+    LENGTH,
+    GRAPHEMES,
+    STRING,
+    NUMBER,
+    IF,
+    WHILE,
+    TABLE,
+    NEW,
+    SET,
+    DELETE,
+    HAS,
+    KEYS,
+    VALUES,
+    MERGE,
+    RECORD,
+    REPEAT,
+    FOR,
+    RUN,
+    TRY,
+    MATCH,
+    STACKTRACE
+}
+
+impl Builtin {
+    pub fn get_for_name(name: &str) -> Option<Builtin> {
+        match name {
+            "get" => Some(Builtin::GET),
+            "import" | "load" => Some(Builtin::IMPORT),
+            "&" | "list" => Some(Builtin::LIST),
+            "+" => Some(Builtin::ADDITION),
+            "-" => Some(Builtin::SUBTRACTION),
+            "*" => Some(Builtin::MULTIPLICATION),
+            "/" => Some(Builtin::DIVISION),
+            "!" => Some(Builtin::NEGATE),
+            "&&" => Some(Builtin::AND),
+            "||" => Some(Builtin::OR),
+            "==" => Some(Builtin::EQUAL),
+            "!=" => Some(Builtin::NOT_EQUAL),
+            "<=" => Some(Builtin::LESS_EQUAL),
+            ">=" => Some(Builtin::GREATER_EQUAL),
+            "<" => Some(Builtin::LESS),
+            ">" => Some(Builtin::GREATER),
+            "??" => Some(Builtin::NULL_COALESCING),
+            "append" => Some(Builtin::APPEND),
+            "brpoint" => Some(Builtin::BRPOINT),
+            "%" => Some(Builtin::MODULO),
+            "div" => Some(Builtin::INTEGER_DIVISION),
+            "is" => Some(Builtin::IS),
+            "print" => Some(Builtin::PRINT),
+            "println" => Some(Builtin::PRINTLN),
+            "eval" => Some(Builtin::EVAL),
+            "break" => Some(Builtin::BREAK),
+            "continue" => Some(Builtin::CONTINUE),
+            "return" => Some(Builtin::RETURN),
+            "error" => Some(Builtin::ERROR),
+            "panic" => Some(Builtin::PANIC),
+            "read" => Some(Builtin::READ),
+            "insert" => Some(Builtin::INSERT),
+            "round" => Some(Builtin::ROUND),
+            "map" => Some(Builtin::MAP),
+            "remove" => Some(Builtin::REMOVE),
+            "replace" => Some(Builtin::REPLACE),
+            "length" => Some(Builtin::LENGTH),
+            "graphemes" => Some(Builtin::GRAPHEMES),
+            "string" => Some(Builtin::STRING),
+            "number" => Some(Builtin::NUMBER),
+            "if" => Some(Builtin::IF),
+            "while" => Some(Builtin::WHILE),
+            "table" | "#" => Some(Builtin::TABLE),
+            "new" => Some(Builtin::NEW),
+            "set" => Some(Builtin::SET),
+            "delete" => Some(Builtin::DELETE),
+            "has" => Some(Builtin::HAS),
+            "keys" => Some(Builtin::KEYS),
+            "values" => Some(Builtin::VALUES),
+            "merge" => Some(Builtin::MERGE),
+            "record" => Some(Builtin::RECORD),
+            "repeat" => Some(Builtin::REPEAT),
+            "for" => Some(Builtin::FOR),
+            "run" => Some(Builtin::RUN),
+            "try" => Some(Builtin::TRY),
+            "match" => Some(Builtin::MATCH),
+            "stacktrace" => Some(Builtin::STACKTRACE),
+            _ => None
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Builtin::GET => "get",
+            Builtin::IMPORT => "import",
+            Builtin::LIST => "list",
+            Builtin::ADDITION => "+",
+            Builtin::SUBTRACTION => "-",
+            Builtin::MULTIPLICATION => "*",
+            Builtin::DIVISION => "/",
+            Builtin::NEGATE => "!",
+            Builtin::AND => "&&",
+            Builtin::OR => "||",
+            Builtin::EQUAL => "==",
+            Builtin::NOT_EQUAL => "!=",
+            Builtin::LESS_EQUAL => "<=",
+            Builtin::GREATER_EQUAL => ">=",
+            Builtin::LESS => "<",
+            Builtin::GREATER => ">",
+            Builtin::NULL_COALESCING => "??",
+            Builtin::APPEND => "append",
+            Builtin::BRPOINT => "brpoint",
+            Builtin::MODULO => "%",
+            Builtin::INTEGER_DIVISION => "div",
+            Builtin::IS => "is",
+            Builtin::PRINT => "print",
+            Builtin::PRINTLN => "println",
+            Builtin::EVAL => "eval",
+            Builtin::BREAK => "break",
+            Builtin::CONTINUE => "continue",
+            Builtin::RETURN => "return",
+            Builtin::ERROR => "error",
+            Builtin::PANIC => "panic",
+            Builtin::READ => "read",
+            Builtin::INSERT => "insert",
+            Builtin::ROUND => "round",
+            Builtin::MAP => "map",
+            Builtin::REMOVE => "remove",
+            Builtin::REPLACE => "replace",
+            Builtin::LENGTH => "length",
+            Builtin::GRAPHEMES => "graphemes",
+            Builtin::STRING => "string",
+            Builtin::NUMBER => "number",
+            Builtin::IF => "if",
+            Builtin::WHILE => "while",
+            Builtin::TABLE => "table",
+            Builtin::NEW => "new",
+            Builtin::SET => "set",
+            Builtin::DELETE => "delete",
+            Builtin::HAS => "has",
+            Builtin::KEYS => "keys",
+            Builtin::VALUES => "values",
+            Builtin::MERGE => "merge",
+            Builtin::RECORD => "record",
+            Builtin::REPEAT => "repeat",
+            Builtin::FOR => "for",
+            Builtin::RUN => "run",
+            Builtin::TRY => "try",
+            Builtin::MATCH => "match",
+            Builtin::STACKTRACE => "stacktrace"
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use std::borrow::Cow;
+use crate::hier::location::Location;
+
+/// Call of a function, which is kept on the call stack while the function runs.
+#[derive(Debug, Clone)]
+pub struct Call {
+    /// Builtins have static names, so they aren't allocated on every call.
+    pub name: Cow<'static, str>,
+    /// Path of the module in which the function was defined. None for builtins and functions provided by the host.
+    pub module: Option<String>,
+    /// Location of the call site.
+    pub location: Location
+}
+
+impl Call {
+    pub fn new(name: impl Into<Cow<'static, str>>, module: Option<String>, location: Location) -> Self {
+        Self {
+            name: name.into(),
+            module,
+            location
+        }
+    }
+
+    pub fn text_representation(&self) -> String {
+        let module = self.module.as_deref().unwrap_or("native");
+        format!("{} ({}) called at {}", self.name, module, self.location.text_representation())
+    }
+}
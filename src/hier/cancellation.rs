@@ -0,0 +1,25 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Stops the code which is running, for example when user presses Ctrl-C. It can be sent to other threads.
+/// The code is stopped before its next expression with an error of kind CANCELLED. Native functions which are running (like reading a line) aren't interrupted.
+#[derive(Debug, Clone, Default)]
+pub struct CancelHandle {
+    cancelled: Arc<AtomicBool>
+}
+
+impl CancelHandle {
+    /// Requests stopping the current run. It has no effect if nothing is running.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Clears the request, so it doesn't stop the next run.
+    pub fn reset(&self) {
+        self.cancelled.store(false, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,220 @@
+use crate::hier::block::Block;
+use crate::hier::builtin::Builtin;
+use crate::hier::expression::Expression;
+use crate::hier::interpolated_string::InterpolatedString;
+use crate::hier::location::Location;
+use crate::hier::types::Type;
+use crate::hier::value::{List, Value};
+
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+#[derive(Debug, Clone)]
+pub enum Instruction {
+    /// Push a constant.
+    CONSTANT(usize),
+    /// Resolve an interpolated string and push it.
+    STRING(usize),
+    /// Push value of a variable.
+    GET(usize),
+    /// Pop a value and push a key-value with it.
+    KEY_VALUE(usize),
+    /// Pop given number of values and push a list made of them.
+    LIST(usize),
+    /// Pop given number of arguments and call a builtin function with them.
+    CALL_BUILTIN(Builtin, usize),
+    /// Pop given number of arguments and call a function with given name with them.
+    CALL(usize, usize),
+    /// Pop given number of arguments (the first one is the object) and call a method or a function with given name with them.
+    CALL_METHOD(usize, usize),
+    /// Pop a value. If it's function arguments, push an anonymous function (or error with a message) and jump. Otherwise, continue to build a list.
+    ANONYMOUS_FUNCTION(Result<usize, usize>, usize),
+    /// Pop result of an expression of a block.
+    POP,
+    /// Error with a message.
+    ERROR(usize)
+}
+
+/// Compiled block. Every instruction has a location, which is set as the current location before it is executed, if it has one.
+#[derive(Debug)]
+pub struct Chunk {
+    pub instructions: Vec<Instruction>,
+    pub locations: Vec<Option<Location>>,
+    pub constants: Vec<Value>,
+    pub names: Vec<String>,
+    pub strings: Vec<InterpolatedString>
+}
+
+pub struct Compiler {
+    chunk: Chunk
+}
+
+impl Compiler {
+    pub fn compile(expressions: &[Expression]) -> Chunk {
+        let mut compiler = Self {
+            chunk: Chunk {
+                instructions: vec![],
+                locations: vec![],
+                constants: vec![],
+                names: vec![],
+                strings: vec![]
+            }
+        };
+
+        if expressions.is_empty() {
+            compiler.emit_constant(Value::NULL, None);
+        }
+
+        for (i, expression) in expressions.iter().enumerate() {
+            compiler.compile_expression(expression);
+
+            if i < expressions.len() - 1 {
+                compiler.emit(Instruction::POP, None);
+            }
+        }
+
+        compiler.chunk
+    }
+
+    fn compile_expression(&mut self, expression: &Expression) {
+        match expression {
+            Expression::STRING(string, location) => {
+                self.chunk.strings.push(string.clone());
+                self.emit(Instruction::STRING(self.chunk.strings.len() - 1), Some(location.clone()));
+            },
+            Expression::NUMBER(number, location) => self.emit_constant(Value::NUMBER(*number), Some(location.clone())),
+            Expression::INTEGER(integer, location) => self.emit_constant(Value::INTEGER(*integer), Some(location.clone())),
+            Expression::BIG_INTEGER(integer, location) => self.emit_constant(Value::BIG_INTEGER(integer.clone()), Some(location.clone())),
+            Expression::DECIMAL(decimal, location) => self.emit_constant(Value::DECIMAL(decimal.clone()), Some(location.clone())),
+            Expression::VALUE(value) => self.emit_constant(value.clone(), Some(Location::empty())),
+            Expression::IDENTIFIER(identifier, location) => self.compile_identifier(identifier, location),
+            Expression::BLOCK(expressions, location) => self.emit_constant(Value::BLOCK(Block::new(expressions.clone())), Some(location.clone())),
+            Expression::KEY_VALUE(key, expression, _) => {
+                self.compile_expression(expression);
+                let key = self.add_name(key);
+                self.emit(Instruction::KEY_VALUE(key), None);
+            },
+            Expression::PROPERTY(expression, identifier, location) => {
+                self.compile_expression(expression);
+                self.emit_constant(Value::STRING(identifier.as_str().into()), None);
+                self.emit(Instruction::CALL_BUILTIN(Builtin::GET, 2), Some(location.clone()));
+            },
+            Expression::LIST(list, location) => self.compile_list(list, location)
+        }
+    }
+
+    fn compile_identifier(&mut self, identifier: &str, location: &Location) {
+        match identifier {
+            "true" => self.emit_constant(Value::BOOL(true), Some(location.clone())),
+            "false" => self.emit_constant(Value::BOOL(false), Some(location.clone())),
+            "null" => self.emit_constant(Value::NULL, Some(location.clone())),
+            _ => {
+                if let Some(a_type) = Type::get_for_name(identifier) {
+                    self.emit_constant(Value::TYPE(a_type), Some(location.clone()));
+                } else {
+                    let name = self.add_name(identifier);
+                    self.emit(Instruction::GET(name), Some(location.clone()));
+                }
+            }
+        }
+    }
+
+    fn compile_list(&mut self, list: &[Expression], location: &Location) {
+        if list.is_empty() {
+            self.emit_constant(Value::LIST(List::new()), Some(location.clone()));
+            return;
+        }
+
+        match &list[0] {
+            Expression::IDENTIFIER(name, _) if name == "|" => {
+                let mut arguments: Vec<String> = Vec::new();
+
+                for expression in &list[1..] {
+                    if let Expression::IDENTIFIER(argument, _) = expression {
+                        arguments.push(argument.clone());
+                    } else {
+                        let message = self.add_name("Function arguments must be identifiers.");
+                        self.emit(Instruction::ERROR(message), Some(location.clone()));
+                        return;
+                    }
+                }
+
+                self.emit_constant(Value::FUNCTION_ARGUMENTS(arguments), Some(location.clone()));
+            },
+            Expression::IDENTIFIER(name, _) => {
+                for expression in &list[1..] {
+                    self.compile_expression(expression);
+                }
+
+                self.emit_call(name, list.len() - 1, location);
+            },
+            Expression::PROPERTY(expression, identifier, _) => {
+                self.compile_expression(expression);
+
+                for expression in &list[1..] {
+                    self.compile_expression(expression);
+                }
+
+                let name = self.add_name(identifier);
+                self.emit(Instruction::CALL_METHOD(name, list.len()), Some(location.clone()));
+            },
+            _ => {
+                self.compile_expression(&list[0]);
+
+                let function = if list.len() != 2 {
+                    Err(self.add_name("Anonymous function's must have 2 arguments: function arguments and a block"))
+                } else if let Expression::BLOCK(block, _) = &list[1] {
+                    Ok(self.add_constant(Value::BLOCK(Block::new(block.clone()))))
+                } else {
+                    Err(self.add_name("Anonymous function's second argument must be a block."))
+                };
+
+                self.emit(Instruction::ANONYMOUS_FUNCTION(function, 0), None);
+                let jump = self.chunk.instructions.len() - 1;
+
+                for expression in list {
+                    self.compile_expression(expression);
+                }
+
+                self.emit(Instruction::LIST(list.len()), None);
+
+                let end = self.chunk.instructions.len();
+                if let Instruction::ANONYMOUS_FUNCTION(_, target) = &mut self.chunk.instructions[jump] {
+                    *target = end;
+                }
+            }
+        }
+    }
+
+    fn emit_call(&mut self, name: &str, arguments_count: usize, location: &Location) {
+        // Call site is the list, not its last argument.
+        if let Some(builtin) = Builtin::get_for_name(name) {
+            self.emit(Instruction::CALL_BUILTIN(builtin, arguments_count), Some(location.clone()));
+        } else {
+            let name = self.add_name(name);
+            self.emit(Instruction::CALL(name, arguments_count), Some(location.clone()));
+        }
+    }
+
+    fn emit_constant(&mut self, value: Value, location: Option<Location>) {
+        let constant = self.add_constant(value);
+        self.emit(Instruction::CONSTANT(constant), location);
+    }
+
+    fn emit(&mut self, instruction: Instruction, location: Option<Location>) {
+        self.chunk.instructions.push(instruction);
+        self.chunk.locations.push(location);
+    }
+
+    fn add_constant(&mut self, value: Value) -> usize {
+        self.chunk.constants.push(value);
+        self.chunk.constants.len() - 1
+    }
+
+    fn add_name(&mut self, name: &str) -> usize {
+        if let Some(index) = self.chunk.names.iter().position(|existing| existing == name) {
+            index
+        } else {
+            self.chunk.names.push(name.to_string());
+            self.chunk.names.len() - 1
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use crate::hier::value::Value;
+
+/// Signal which stops execution of the code until it reaches the place which handles it. Unlike errors, it isn't a value.
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+#[derive(Clone, Debug)]
+pub enum ControlFlow {
+    /// Ends the nearest user-defined function call with a value.
+    RETURN(Value),
+    /// Ends the nearest loop (or the loop with the label, if there is one) and makes it evaluate to a value.
+    BREAK(Option<String>, Value),
+    /// Skips to the next iteration of the nearest loop (or the loop with the label, if there is one).
+    CONTINUE(Option<String>)
+}
+
+impl ControlFlow {
+    /// Checks if the signal is handled by a loop with a given label.
+    pub fn targets_loop(&self, label: &Option<String>) -> bool {
+        let target = match self {
+            ControlFlow::BREAK(target, _) | ControlFlow::CONTINUE(target) => target,
+            ControlFlow::RETURN(_) => return false
+        };
+
+        target.is_none() || target == label
+    }
+}
@@ -0,0 +1,370 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+use bigdecimal::BigDecimal;
+use num_bigint::BigInt;
+use crate::hier::environment::Environment;
+use crate::hier::hier_error::HierError;
+use crate::hier::user_data::UserData;
+use crate::hier::value::{NativeFunction, Table, Value};
+
+/// Conversion of a Hier value to a Rust type. Used for arguments of typed native functions.
+pub trait FromValue: Sized {
+    /// Returns None if the value has a different type.
+    fn from_value(value: &Value) -> Option<Self>;
+
+    /// Name of the type used in errors, for example "List of Number".
+    fn type_name() -> String;
+}
+
+/// Conversion of a Rust type to a Hier value. Used for results of typed native functions.
+pub trait IntoValue {
+    fn into_value(self) -> Value;
+}
+
+impl FromValue for Value {
+    fn from_value(value: &Value) -> Option<Self> {
+        Some(value.clone())
+    }
+
+    fn type_name() -> String {
+        "any value".to_string()
+    }
+}
+
+impl IntoValue for Value {
+    fn into_value(self) -> Value {
+        self
+    }
+}
+
+impl FromValue for f64 {
+    fn from_value(value: &Value) -> Option<Self> {
+        value.as_number()
+    }
+
+    fn type_name() -> String {
+        "Number".to_string()
+    }
+}
+
+impl IntoValue for f64 {
+    fn into_value(self) -> Value {
+        Value::NUMBER(self)
+    }
+}
+
+impl FromValue for i64 {
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::INTEGER(_) | Value::BIG_INTEGER(_) => value.as_integer(),
+            Value::NUMBER(number) if number.fract() == 0.0 && *number >= i64::MIN as f64 && *number <= i64::MAX as f64 => Some(*number as i64),
+            _ => None
+        }
+    }
+
+    fn type_name() -> String {
+        "Integer".to_string()
+    }
+}
+
+impl IntoValue for i64 {
+    fn into_value(self) -> Value {
+        Value::INTEGER(self)
+    }
+}
+
+impl FromValue for BigInt {
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::INTEGER(integer) => Some(BigInt::from(*integer)),
+            Value::BIG_INTEGER(integer) => Some((**integer).clone()),
+            _ => None
+        }
+    }
+
+    fn type_name() -> String {
+        "Integer".to_string()
+    }
+}
+
+impl IntoValue for BigInt {
+    fn into_value(self) -> Value {
+        Value::BIG_INTEGER(Rc::new(self))
+    }
+}
+
+/// Integers are also converted to decimals, but floats aren't, because they aren't exact.
+impl FromValue for BigDecimal {
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::DECIMAL(decimal) => Some((**decimal).clone()),
+            _ => BigInt::from_value(value).map(BigDecimal::from)
+        }
+    }
+
+    fn type_name() -> String {
+        "Decimal".to_string()
+    }
+}
+
+impl IntoValue for BigDecimal {
+    fn into_value(self) -> Value {
+        Value::DECIMAL(Rc::new(self))
+    }
+}
+
+impl FromValue for bool {
+    fn from_value(value: &Value) -> Option<Self> {
+        if let Value::BOOL(boolean) = value {
+            Some(*boolean)
+        } else {
+            None
+        }
+    }
+
+    fn type_name() -> String {
+        "Bool".to_string()
+    }
+}
+
+impl IntoValue for bool {
+    fn into_value(self) -> Value {
+        Value::BOOL(self)
+    }
+}
+
+impl FromValue for String {
+    fn from_value(value: &Value) -> Option<Self> {
+        if let Value::STRING(string) = value {
+            Some(string.to_string())
+        } else {
+            None
+        }
+    }
+
+    fn type_name() -> String {
+        "String".to_string()
+    }
+}
+
+impl IntoValue for String {
+    fn into_value(self) -> Value {
+        Value::STRING(self.into())
+    }
+}
+
+impl IntoValue for &str {
+    fn into_value(self) -> Value {
+        Value::STRING(self.to_string().into())
+    }
+}
+
+impl<T: FromValue> FromValue for Vec<T> {
+    fn from_value(value: &Value) -> Option<Self> {
+        if let Value::LIST(list) = value {
+            list.iter().map(T::from_value).collect()
+        } else {
+            None
+        }
+    }
+
+    fn type_name() -> String {
+        format!("List of {}", T::type_name())
+    }
+}
+
+impl<T: IntoValue> IntoValue for Vec<T> {
+    fn into_value(self) -> Value {
+        Value::LIST(self.into_iter().map(T::into_value).collect())
+    }
+}
+
+impl<T: FromValue> FromValue for HashMap<String, T> {
+    fn from_value(value: &Value) -> Option<Self> {
+        if let Value::TABLE(table) = value {
+            table.iter().map(|(key, value)| Some((key.clone(), T::from_value(value)?))).collect()
+        } else {
+            None
+        }
+    }
+
+    fn type_name() -> String {
+        format!("Table of {}", T::type_name())
+    }
+}
+
+impl<T: IntoValue> IntoValue for HashMap<String, T> {
+    fn into_value(self) -> Value {
+        Value::TABLE(self.into_iter().map(|(key, value)| (key, value.into_value())).collect())
+    }
+}
+
+/// Table of any values, shared with the value without copying.
+impl FromValue for Table {
+    fn from_value(value: &Value) -> Option<Self> {
+        if let Value::TABLE(table) = value {
+            Some(table.clone())
+        } else {
+            None
+        }
+    }
+
+    fn type_name() -> String {
+        "Table".to_string()
+    }
+}
+
+impl IntoValue for Table {
+    fn into_value(self) -> Value {
+        Value::TABLE(self)
+    }
+}
+
+impl<T: FromValue> FromValue for Option<T> {
+    fn from_value(value: &Value) -> Option<Self> {
+        if let Value::NULL = value {
+            Some(None)
+        } else {
+            T::from_value(value).map(Some)
+        }
+    }
+
+    fn type_name() -> String {
+        format!("{} or Null", T::type_name())
+    }
+}
+
+impl<T: IntoValue> IntoValue for Option<T> {
+    fn into_value(self) -> Value {
+        match self {
+            Some(value) => value.into_value(),
+            None => Value::NULL
+        }
+    }
+}
+
+impl FromValue for Rc<dyn UserData> {
+    fn from_value(value: &Value) -> Option<Self> {
+        if let Value::USER_DATA(object) = value {
+            Some(object.clone())
+        } else {
+            None
+        }
+    }
+
+    fn type_name() -> String {
+        "host object".to_string()
+    }
+}
+
+impl IntoValue for Rc<dyn UserData> {
+    fn into_value(self) -> Value {
+        Value::USER_DATA(self)
+    }
+}
+
+impl IntoValue for () {
+    fn into_value(self) -> Value {
+        Value::NULL
+    }
+}
+
+/// Result of a typed native function: a value or a result with an error, which stops the code.
+pub trait IntoResult {
+    fn into_result(self, environment: &mut Environment) -> Result<Value, HierError>;
+}
+
+impl<T: IntoValue> IntoResult for T {
+    fn into_result(self, _environment: &mut Environment) -> Result<Value, HierError> {
+        Ok(self.into_value())
+    }
+}
+
+/// Message of the error is reported at the location of the call.
+impl<T: IntoValue> IntoResult for Result<T, String> {
+    fn into_result(self, environment: &mut Environment) -> Result<Value, HierError> {
+        match self {
+            Ok(value) => Ok(value.into_value()),
+            Err(message) => environment.error(&message)
+        }
+    }
+}
+
+impl<T: IntoValue> IntoResult for Result<T, HierError> {
+    fn into_result(self, _environment: &mut Environment) -> Result<Value, HierError> {
+        self.map(T::into_value)
+    }
+}
+
+/// Rust function which can be added to Hier. It's implemented for functions (up to 6 arguments) whose arguments implement FromValue and result implements IntoResult.
+/// Such functions can also take the environment as the first argument. Arguments count and types are checked automatically.
+pub trait IntoNativeFunction<Arguments> {
+    /// Returns the native function and its number of arguments.
+    fn into_native_function(self, name: String) -> (NativeFunction, i64);
+}
+
+fn convert<T: FromValue>(environment: &Environment, name: &str, index: usize, value: &Value) -> Result<T, HierError> {
+    match T::from_value(value) {
+        Some(value) => Ok(value),
+        None => environment.error(&format!("Argument {} of {} must be {}, but {} of type {} was found.", index, name, T::type_name(), value.text_representation(), value.get_type().text_representation()))
+    }
+}
+
+macro_rules! impl_into_native_function {
+    ($count:literal; $($type:ident $argument:ident $index:literal),*) => {
+        impl<F, R, $($type),*> IntoNativeFunction<($($type,)*)> for F
+            where F: Fn($($type),*) -> R + 'static, R: IntoResult, $($type: FromValue),* {
+            // Functions without arguments don't use the name and arguments.
+            #[allow(unused_variables)]
+            fn into_native_function(self, name: String) -> (NativeFunction, i64) {
+                (Rc::new(move |environment: &mut Environment, arguments: Vec<Value>| {
+                    $(let $argument = convert::<$type>(environment, &name, $index, arguments.get($index - 1).unwrap_or(&Value::NULL))?;)*
+                    self($($argument),*).into_result(environment)
+                }), $count)
+            }
+        }
+
+        impl<F, R, $($type),*> IntoNativeFunction<(Environment, $($type,)*)> for F
+            where F: Fn(&mut Environment, $($type),*) -> R + 'static, R: IntoResult, $($type: FromValue),* {
+            // Functions without arguments don't use the name and arguments.
+            #[allow(unused_variables)]
+            fn into_native_function(self, name: String) -> (NativeFunction, i64) {
+                (Rc::new(move |environment: &mut Environment, arguments: Vec<Value>| {
+                    $(let $argument = convert::<$type>(environment, &name, $index, arguments.get($index - 1).unwrap_or(&Value::NULL))?;)*
+                    self(environment, $($argument),*).into_result(environment)
+                }), $count)
+            }
+        }
+    };
+}
+
+impl_into_native_function!(0;);
+impl_into_native_function!(1; A a 1);
+impl_into_native_function!(2; A a 1, B b 2);
+impl_into_native_function!(3; A a 1, B b 2, C c 3);
+impl_into_native_function!(4; A a 1, B b 2, C c 3, D d 4);
+impl_into_native_function!(5; A a 1, B b 2, C c 3, D d 4, E e 5);
+impl_into_native_function!(6; A a 1, B b 2, C c 3, D d 4, E e 5, G g 6);
+
+/// Arguments of a builtin converted to a tuple of Rust types. Count and types are checked like arguments of typed native functions.
+pub(crate) trait FromArguments: Sized {
+    fn from_arguments(environment: &Environment, name: &str, arguments: &[Value]) -> Result<Self, HierError>;
+}
+
+macro_rules! impl_from_arguments {
+    ($count:literal; $($type:ident $index:literal),*) => {
+        impl<$($type: FromValue),*> FromArguments for ($($type,)*) {
+            fn from_arguments(environment: &Environment, name: &str, arguments: &[Value]) -> Result<Self, HierError> {
+                if arguments.len() != $count {
+                    return environment.error(&format!("Function {} expects {} arguments, but {} were provided.", name, $count, arguments.len()));
+                }
+
+                Ok(($(convert::<$type>(environment, name, $index, &arguments[$index - 1])?,)*))
+            }
+        }
+    };
+}
+
+impl_from_arguments!(0;);
+impl_from_arguments!(1; A 1);
+impl_from_arguments!(2; A 1, B 2);
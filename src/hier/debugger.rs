@@ -1,10 +1,9 @@
 use std::io;
 use std::io::Write;
-use crate::exit_handler;
 use crate::hier::environment::Environment;
 
 pub(crate) fn debug(environment: &mut Environment, break_function: &String) {
-    if break_function != "" && !environment.is_a_step {
+    if !break_function.is_empty() && !environment.is_a_step {
         println!("Breakpoint {} at {}:{} in {}",
                  break_function,
                  environment.current_interpreting_location.line_number,
@@ -25,7 +24,7 @@ pub(crate) fn debug(environment: &mut Environment, break_function: &String) {
         let mut line = String::new();
         if let Err(error) = io::stdin().read_line(&mut line) {
             eprintln!("Failed to read line: {}.", error);
-            exit_handler();
+            exit(environment);
         };
 
         line = line.trim().to_string();
@@ -54,15 +53,12 @@ pub(crate) fn debug(environment: &mut Environment, break_function: &String) {
                     },
                     Err(_) => {
                         let mut idx: i32 = -1;
-                        let mut i = 0;
 
-                        for br in environment.breakpoints.clone() {
-                            if br == argument {
-                                idx = i;
+                        for (i, br) in environment.breakpoints.iter().enumerate() {
+                            if *br == argument {
+                                idx = i as i32;
                                 break;
                             }
-
-                            i += 1;
                         }
 
                         if idx == -1 {
@@ -74,10 +70,8 @@ pub(crate) fn debug(environment: &mut Environment, break_function: &String) {
                 }
             },
             "lib" | "libreak" => {
-                let mut i = 0;
-                for br in environment.breakpoints.clone() {
+                for (i, br) in environment.breakpoints.iter().enumerate() {
                     println!("{} - {}", i, br);
-                    i += 1;
                 }
             },
             "c" | "continue" => {
@@ -100,16 +94,22 @@ pub(crate) fn debug(environment: &mut Environment, break_function: &String) {
                 println!("{}", environment.current_interpreting_expression.get_representation());
             },
             "p" | "print" => {
-                println!("{:?}", environment.get(argument));
-            },
-            "x" | "exit" => {
-                (environment.exit_handler)();
+                match environment.get(argument) {
+                    Ok(value) => println!("{:?}", value),
+                    Err(error) => println!("Error: {}", error.message)
+                }
             },
+            "x" | "exit" => exit(environment),
             _ =>  println!("Unknown command: {}", line)
         }
     }
 }
 
+fn exit(environment: &Environment) -> ! {
+    (environment.exit_handler)();
+    std::process::exit(0)
+}
+
 fn print_help() {
     println!("== HDB help ==");
     println!("Notation:");
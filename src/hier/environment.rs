@@ -1,39 +1,75 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::mem;
+use std::rc::Rc;
+use crate::hier::block::Block;
 use crate::hier::expression::Expression;
+use crate::hier::frame::Frame;
 use crate::hier::interpreter::warning;
 use crate::hier::location::Location;
 use crate::hier::{debugger, report};
-use crate::hier::value::Value;
-
-
-type Scope = u64;
-
-#[derive(Hash, Eq, PartialEq, Debug, Clone)]
-pub struct VariableId(pub Scope, pub String);
+use crate::hier::backend::Backend;
+use crate::hier::builtin::Builtin;
+use crate::hier::call::Call;
+use crate::hier::cancellation::CancelHandle;
+use crate::hier::control_flow::ControlFlow;
+use crate::hier::error_value::ErrorValue;
+use crate::hier::function::Function;
+use crate::hier::hier_error::{HierError, HierErrorKind};
+use crate::hier::limits::{Budget, Limit, Limits};
+use crate::hier::pattern::Pattern;
+use crate::hier::policy::Policy;
+use crate::hier::record::Record;
+use crate::hier::types::Type;
+use crate::hier::value::{find_field, Value};
+
+
+/// Reads contents of a module with given path. Returns a message if the module can't be read.
+pub type ModuleReader = Rc<dyn Fn(String) -> Result<String, String>>;
+/// Ends the program immediately, for example when user quits the debugger. If it returns, the process is exited.
+pub type ExitHandler = Rc<dyn Fn()>;
+/// Variables of the global scope of a module.
+pub(crate) type Globals = HashMap<String, Value>;
 
 #[derive(Clone)]
 pub struct Environment {
-    pub scope: Scope,
-    pub path: String,
-    pub values: HashMap<VariableId, Value>,
-    pub code: Expression,
+    /// Frame of the current scope. None when in the global scope.
+    pub(crate) frame: Option<Rc<RefCell<Frame>>>,
+    /// Shared with functions defined in the module, so they see its globals when called from other modules.
+    pub(crate) globals: Rc<RefCell<Globals>>,
+    pub(crate) path: String,
+    pub(crate) code: Expression,
     is_in_repl: bool,
-    pub module_reader: fn(String) -> String,
-    pub exit_handler: fn() -> !,
-    pub current_interpreting_location: Location,
-    pub current_interpreting_expression: Expression,
-    pub is_debugging: bool,
-    pub breakpoints: Vec<String>,
-    pub is_a_step: bool,
-    pub should_step_into: bool
+    pub(crate) module_reader: ModuleReader,
+    pub(crate) exit_handler: ExitHandler,
+    pub(crate) current_interpreting_location: Location,
+    pub(crate) current_interpreting_expression: Expression,
+    pub(crate) is_debugging: bool,
+    pub(crate) breakpoints: Vec<String>,
+    pub(crate) is_a_step: bool,
+    pub(crate) should_step_into: bool,
+    pub(crate) backend: Backend,
+    /// Signal which is currently unwinding the code, for example a return.
+    pub(crate) control_flow: Option<ControlFlow>,
+    /// Calls of functions which are currently running. The innermost call is the last one.
+    pub(crate) call_stack: Vec<Call>,
+    /// Operations which the code is allowed to do.
+    pub(crate) policy: Rc<Policy>,
+    /// Directory of the main module, used to check imports.
+    pub(crate) root: String,
+    /// Resources used by the current run and their limits.
+    pub(crate) budget: Rc<Budget>,
+    /// Stops the code when the host cancels it.
+    pub(crate) cancellation: CancelHandle
 }
 
 impl Environment {
-    pub fn new(is_in_repl: bool, path: String, module_reader: fn(String) -> String, exit_handler: fn() -> !, is_debugging: bool, breakpoints: Vec<String>) -> Self {
+    pub(crate) fn new(is_in_repl: bool, path: String, module_reader: ModuleReader, exit_handler: ExitHandler, is_debugging: bool, breakpoints: Vec<String>) -> Self {
         Self {
-            scope: 0,
-            values: HashMap::new(),
+            frame: None,
+            globals: Rc::new(RefCell::new(HashMap::new())),
             code: Expression::LIST(vec![], Location::empty()),
+            root: module_directory(&path),
             path,
             is_in_repl,
             module_reader,
@@ -43,176 +79,251 @@ impl Environment {
             is_debugging,
             breakpoints,
             is_a_step: false,
-            should_step_into: false
+            should_step_into: false,
+            backend: Backend::VM,
+            control_flow: None,
+            call_stack: vec![],
+            policy: Rc::new(Policy::default()),
+            budget: Rc::new(Budget::new(Limits::default())),
+            cancellation: CancelHandle::default()
         }
     }
 
-    pub fn error(&self, error: &str) -> ! {
-        report(error, self.current_interpreting_location.clone());
+    /// Creates a fatal error at the current location, which stops execution of the code and is returned to the host.
+    pub fn error<T>(&self, error: &str) -> Result<T, HierError> {
+        if self.is_debugging {
+            report(error, self.current_interpreting_location.clone());
 
-        if self.is_in_repl {
-            panic!("");
-        } else {
-            if self.is_debugging {
-                let mut editable = self.clone();
-                debugger::debug(&mut editable, &String::from("ERROR"));
-            }
-
-            (self.exit_handler)()
+            let mut editable = self.clone();
+            debugger::debug(&mut editable, &String::from("ERROR"));
         }
+
+        Err(HierError::new(error.to_string(), self.current_interpreting_location.clone(), self.call_stack.clone()))
     }
 
-    pub fn new_with_code(code: Expression, is_in_repl: bool, module_reader: fn(String) -> String, exit_handler: fn() -> !, is_debugging: bool, breakpoints: Vec<String>) -> Self {
-        Self {
-            scope: 0,
-            values: HashMap::new(),
-            code,
-            path: String::new(),
-            is_in_repl,
-            module_reader,
-            exit_handler,
-            current_interpreting_location: Location::empty(),
-            current_interpreting_expression: Expression::VALUE(Value::NULL),
-            is_debugging,
-            breakpoints,
-            is_a_step: false,
-            should_step_into: false
+    /// Starts a new run, which has its own limits and isn't affected by cancellations of previous runs.
+    pub(crate) fn start_run(&self) {
+        self.budget.start();
+        self.cancellation.reset();
+    }
+
+    /// Counts an evaluated expression or block. The code is stopped here, if it exceeded its limits or was cancelled.
+    pub(crate) fn step(&self) -> Result<(), HierError> {
+        if self.cancellation.is_cancelled() {
+            self.cancellation.reset();
+
+            let mut error = HierError::new("Code was cancelled.".to_string(), self.current_interpreting_location.clone(), self.call_stack.clone());
+            error.kind = HierErrorKind::CANCELLED;
+            return Err(error);
         }
+
+        self.enforce(self.budget.step())
     }
-    pub fn begin_scope(&mut self) {
-        self.scope += 1;
+
+    /// Turns an exceeded limit into a fatal error of kind LIMIT. It doesn't start the debugger, because nothing in the code is wrong.
+    pub(crate) fn enforce(&self, usage: Result<(), Limit>) -> Result<(), HierError> {
+        let limit = match usage {
+            Ok(()) => return Ok(()),
+            Err(limit) => limit
+        };
+
+        let limits = self.budget.limits;
+
+        let message = match limit {
+            Limit::STEPS => format!("Code exceeded the limit of {} steps.", limits.steps.unwrap_or_default()),
+            Limit::TIME => format!("Code exceeded the time limit of {} seconds.", limits.time.unwrap_or_default().as_secs_f64()),
+            Limit::DEPTH => format!("Code exceeded the limit of {} nested calls.", limits.depth.unwrap_or_default()),
+            Limit::VALUE_SIZE => format!("Value exceeded the size limit of {} bytes for a single value.", limits.value_size.unwrap_or_default())
+        };
+
+        let mut error = HierError::new(message, self.current_interpreting_location.clone(), self.call_stack.clone());
+        error.kind = HierErrorKind::LIMIT(limit);
+        Err(error)
     }
 
-    pub fn end_scope(&mut self) {
-        if self.scope == 0 {
-            self.error("Ended scope that didn't exist.");
-        } else {
-            for (key, _) in self.values.clone() {
-                if key.0 == self.scope {
-                    self.values.remove(&key);
-                }
-            }
+    /// Operations which the code is allowed to do. Native functions of the host should check it before doing such operations.
+    pub fn policy(&self) -> &Policy {
+        &self.policy
+    }
 
-            self.scope -= 1;
+    /// Creates an error value of a given kind at the current location.
+    pub fn make_error(&self, kind: &str, message: String) -> Value {
+        Value::ERROR(Rc::new(ErrorValue::new(kind.to_string(), message, self.current_interpreting_location.clone(), self.call_stack.clone())))
+    }
+
+    pub(crate) fn begin_scope(&mut self) {
+        self.frame = Some(Frame::new(self.frame.take()));
+    }
+
+    pub(crate) fn end_scope(&mut self) -> Result<(), HierError> {
+        if let Some(frame) = self.frame.take() {
+            self.frame = frame.borrow().parent.clone();
+            Ok(())
+        } else {
+            self.error("Ended scope that didn't exist.")
         }
     }
 
-    pub fn get(&self, key: String) -> Value {
+    pub(crate) fn get(&self, key: String) -> Result<Value, HierError> {
         if key.contains("::") {
             // We can unwrap, because it is nil only if the delimiter is not present, but we can be sure, because we checked.
             let path = key.split_once("::").unwrap();
 
-            if path.1.to_string().chars().collect::<Vec<char>>()[0] == '_' {
-                return Value::NULL;
+            if path.1.is_empty() {
+                return self.error(&format!("Name of a member of {} can't be empty.", path.0));
             }
 
-            let environment = self.get(path.0.to_string());
+            if path.1.starts_with('_') {
+                return Ok(Value::NULL);
+            }
+
+            let environment = self.get(path.0.to_string())?;
 
             if let Value::ENVIRONMENT(target_environment) = environment {
                 target_environment.get(path.1.to_string())
             } else if let Value::NULL = environment {
-                return Value::NULL;
+                Ok(Value::NULL)
             } else {
                 let target = path.0;
-                self.error(&format!("{target} is not an environment."));
+                self.error(&format!("{target} is not an environment."))
             }
         } else {
-            if let Some(value) = self.values.get(&VariableId(self.scope, key.clone())) {
-                value.clone()
-            } else {
-                if self.scope == 0 {
-                    Value::NULL
-                } else {
-                    self.get_in_scope(key, self.scope - 1)
+            if let Some(frame) = &self.frame {
+                if let Some(value) = frame.borrow().get(&key) {
+                    return Ok(value);
                 }
             }
+
+            Ok(self.globals.borrow().get(&key).cloned().unwrap_or(Value::NULL))
         }
     }
 
-    fn get_in_scope(&self, key: String, scope: Scope) -> Value {
-        if key.contains("::") {
-            // We can unwrap, because it is nil only if the delimiter is not present, but we can be sure, because we checked.
-            let path = key.split_once("::").unwrap();
+    /// Declare a new variable in current scope and assign it some value
+    pub(crate) fn declare(&mut self, key: String, value: Value) -> Result<(), HierError> {
+        let exists = match &self.frame {
+            Some(frame) => frame.borrow().values.contains_key(&key),
+            None => self.globals.borrow().contains_key(&key)
+        };
+
+        if exists && !self.is_in_repl {
+            return self.error(&format!("Variable '{}' already exists in current scope.", key));
+        }
 
-            if path.1.to_string().chars().collect::<Vec<char>>()[0] == '_' {
-                return Value::NULL;
-            }
+        match &self.frame {
+            Some(frame) => { frame.borrow_mut().values.insert(key, value); },
+            None => { self.globals.borrow_mut().insert(key, value); }
+        }
 
-            let environment = self.values.get(&VariableId(scope, key.clone()));
+        Ok(())
+    }
 
-            if let Some(value) = environment {
-                if let Value::ENVIRONMENT(target_environment) = value {
-                    target_environment.get(key)
-                } else if let Value::NULL = value {
-                    return Value::NULL;
-                } else {
-                    let target = path.0;
-                    self.error(&format!("{target} is not an environment."));
-                }
-            } else {
-                Value::NULL
-            }
-        } else {
-            if let Some(value) = self.values.get(&VariableId(scope, key.clone())) {
-                value.clone()
+    /// Assign a value to a variable and error when it already exists
+    pub(crate) fn assign(&mut self, key: String, value: Value) -> Result<(), HierError> {
+        let value = match &self.frame {
+            Some(frame) => frame.borrow_mut().assign(&key, value),
+            None => Some(value)
+        };
+
+        if let Some(value) = value {
+            if let Some(variable) = self.globals.borrow_mut().get_mut(&key) {
+                *variable = value;
             } else {
-                if scope == 0 {
-                    Value::NULL
-                } else {
-                    self.get_in_scope(key, scope - 1)
-                }
+                return self.error(&format!("Variable {} doesn't exist.", key));
             }
         }
+
+        Ok(())
     }
 
-    /// Declare a new variable in current scope and assign it some value
-    pub fn declare(&mut self, key: String, value: Value) {
-        if self.is_in_repl {
-            self.values.insert(VariableId(self.scope, key), value);
-        } else {
-            if let Some(_) = self.values.get(&VariableId(self.scope, key.clone())) {
-                self.error(&format!("Variable '{}' already exists in current scope.", key));
-            } else {
-                self.values.insert(VariableId(self.scope, key), value);
-            }
+    /// Reads types used by the pattern from variables, so it can be matched.
+    pub(crate) fn resolve_pattern(&self, pattern: &Pattern) -> Result<Pattern, HierError> {
+        match pattern.resolve(&mut |name| self.get(name.to_string()).unwrap_or(Value::NULL)) {
+            Ok(pattern) => Ok(pattern),
+            Err(message) => self.error(&message)
         }
     }
 
-    /// Assign a value to a variable and error when it already exists
-    pub fn assign(&mut self, key: String, value: Value) {
-        if self.values.contains_key(&VariableId(self.scope, key.clone())) {
-            self.values.insert(VariableId(self.scope, key.clone()), value);
-        } else {
-            if self.scope == 0 {
-                self.error(&format!("Variable {} doesn't exist.", key));
+    /// Declares (or assigns) variables bound by matching the value with the pattern. Arguments are the pattern and the value, made by the parser from destructuring.
+    fn destructure(&mut self, arguments: Vec<Value>, is_declaration: bool) -> Result<Value, HierError> {
+        let [Value::PATTERN(pattern), value] = &arguments[..] else {
+            return self.error("Destructuring requires a pattern and 1 value.");
+        };
+
+        let pattern = self.resolve_pattern(&pattern.0)?;
+        let mut bindings = vec![];
+
+        if !pattern.matches(value, &mut bindings) {
+            return self.error(&format!("Value {} doesn't match the pattern it's destructured with.", value.text_representation()));
+        }
+
+        for (name, bound) in bindings {
+            if is_declaration {
+                self.declare(name, bound)?;
             } else {
-                self.assign_in_scope(key, value, self.scope - 1)
+                self.assign(name, bound)?;
             }
         }
+
+        Ok(value.clone())
     }
 
-    fn assign_in_scope(&mut self, key: String, value: Value, scope: Scope) {
-        if self.values.contains_key(&VariableId(scope, key.clone())) {
-            self.values.insert(VariableId(scope, key.clone()), value);
-        } else {
-            if scope == 0 {
-                self.error(&format!("Variable {} doesn't exist.", key));
-            } else {
-                self.assign_in_scope(key, value, scope - 1);
-            }
+    fn call_user_defined_function(&mut self, name: &str, arguments: Vec<Value>) -> Result<Value, HierError> {
+        let function = self.get(name.to_string())?;
+        self.call_value(name, function, arguments)
+    }
+
+    /// Makes a function defined in the current scope.
+    pub(crate) fn make_function(&self, parameters: Vec<String>, block: Block) -> Value {
+        Value::FUNCTION(Rc::new(Function {
+            parameters,
+            block,
+            frame: self.frame.clone(),
+            globals: Rc::downgrade(&self.globals),
+            module: self.path.clone()
+        }))
+    }
+
+    /// Declares parameters of a user-defined function in its frame and runs its block.
+    fn run_function(&mut self, parameters: Vec<String>, catchall_name: String, arguments: Vec<Value>, block: Block) -> Result<Value, HierError> {
+        for (parameter, argument) in parameters.iter().zip(&arguments) {
+            self.declare(parameter.clone(), argument.clone())?;
+        }
+
+        if !catchall_name.is_empty() {
+            let catchall_list: Vec<Value> = arguments[parameters.len()..].to_vec();
+            self.declare(catchall_name, Value::LIST(catchall_list.into()))?;
+        }
+
+        // Every call uses a lot of the native stack, so it's extended for deep recursion.
+        let value = stacker::maybe_grow(256 * 1024, 4 * 1024 * 1024, || self.interpret_block(block))?;
+
+        // Loops of the caller can't be broken from inside of a function.
+        match self.control_flow.take() {
+            Some(ControlFlow::RETURN(returned_value)) => Ok(returned_value),
+            Some(ControlFlow::BREAK(_, _)) => self.error("Break must be used inside of a loop (or a loop with its label doesn't exist)."),
+            Some(ControlFlow::CONTINUE(_)) => self.error("Continue must be used inside of a loop (or a loop with its label doesn't exist)."),
+            None => Ok(value)
         }
     }
 
-    fn call_user_defined_function(&mut self, name: &String, arguments: Vec<Value>) -> Value {
-        if let Value::FUNCTION(parameters, block) = self.get(name.clone().to_string()) {
-            let does_catchall_exist = parameters.len() != 0
+    /// Checks that a new value, like a result of a function or a list literal, fits in the limit of size of a single value.
+    pub(crate) fn check_size(&self, value: Value) -> Result<Value, HierError> {
+        self.enforce(self.budget.check_size(&value))?;
+        Ok(value)
+    }
+
+    /// Calls a function value (user-defined or native). Name is used in errors and the call stack.
+    pub fn call_value(&mut self, name: &str, function: Value, arguments: Vec<Value>) -> Result<Value, HierError> {
+        if let Value::FUNCTION(function) = function {
+            let parameters = function.parameters.clone();
+            let does_catchall_exist = !parameters.is_empty()
                 && parameters.last().unwrap().len() > 1
                 && parameters.last().unwrap().chars().nth(0).unwrap() == '&';
 
             if does_catchall_exist && arguments.len() < parameters.len() {
-                self.error(&format!("Function {} expects at least {} arguments, but {} were provided.", name, parameters.len(), arguments.len()));
+                return self.error(&format!("Function {} expects at least {} arguments, but {} were provided.", name, parameters.len(), arguments.len()));
             } else if !does_catchall_exist && arguments.len() != parameters.len() {
-                self.error(&format!("Function {} expects {} arguments, but {} were provided.", name, parameters.len(), arguments.len()));
+                return self.error(&format!("Function {} expects {} arguments, but {} were provided.", name, parameters.len(), arguments.len()));
             }
 
             let mut catchall_name = String::new();
@@ -228,188 +339,266 @@ impl Environment {
                 parameters.remove(parameters.len() - 1);
             }
 
-            {
-                self.begin_scope();
-
-                for (i, argument) in arguments.iter().enumerate() {
-                    if i >= parameters.len() {
-                        break;
-                    }
-
-                    if i == parameters.len() && parameters[i].chars().nth(0).unwrap() == '&' {
-                        break;
-                    }
-
-                    self.declare(parameters[i].clone(), argument.clone());
-                }
-
-                if does_catchall_exist {
-                    let mut catchall_list: Vec<Value> = vec![];
-
-                    let mut i = parameters.len();
-                    while i < arguments.len() {
-                        catchall_list.push(arguments[i].clone());
-                        i += 1;
-                    }
-
-                    self.declare(catchall_name, Value::LIST(catchall_list));
-                }
-
-                if let Value::BLOCK(block) = (*block).clone() {
-                    let value = self.interpret_block(block);
-                    self.end_scope();
-                    value
-                } else {
-                    warning(&format!("Function {} doesn't have a block.", name));
-                    self.end_scope();
-                    Value::NULL
-                }
-            }
-        } else if let Value::NATIVE_FUNCTION(function, arity) = self.get(name.clone().to_string()) {
+            self.enforce(self.budget.enter())?;
+            self.call_stack.push(Call::new(name.to_string(), Some(function.module.clone()), self.current_interpreting_location.clone()));
+
+            // Function sees variables from where it was defined, not from where it's called. If its module no longer exists, it has no globals.
+            let caller_frame = self.frame.replace(Frame::new(function.frame.clone()));
+            let caller_globals = mem::replace(&mut self.globals, function.globals.upgrade().unwrap_or_default());
+            // Imports and functions defined in the function belong to its module.
+            let caller_path = mem::replace(&mut self.path, function.module.clone());
+
+            let result = self.run_function(parameters, catchall_name, arguments, function.block.clone());
+
+            // State of the caller is restored even after an error, because the caller (like a host function) can continue.
+            self.frame = caller_frame;
+            self.globals = caller_globals;
+            self.path = caller_path;
+            self.call_stack.pop();
+            self.budget.leave();
+            result
+        } else if let Value::NATIVE_FUNCTION(function, arity) = function {
             if arity != -1 && arguments.len() != arity as usize {
-                self.error(&format!("Function {} expects {} arguments, but {} were provided.", name, arity, arguments.len()));
+                return self.error(&format!("Function {} expects {} arguments, but {} were provided.", name, arity, arguments.len()));
             }
 
-            function(self, arguments)
+            self.call_stack.push(Call::new(name.to_string(), None, self.current_interpreting_location.clone()));
+            let result = function(self, arguments).and_then(|value| self.check_size(value));
+            self.call_stack.pop();
+            result
+        } else if let Value::TYPE(Type::RECORD(record_type)) = function {
+            match Record::construct(record_type, arguments) {
+                Ok(record) => Ok(Value::RECORD(Rc::new(record))),
+                Err(message) => self.error(&message)
+            }
         } else {
             warning(&format!("Function {} doesn't exist or is not a function.", name));
-            Value::NULL
+            Ok(Value::NULL)
         }
     }
 
-    pub fn call_function(&mut self, name: &String, arguments: Vec<Value>) -> Value {
+    pub(crate) fn call_function(&mut self, name: &String, arguments: Vec<Value>) -> Result<Value, HierError> {
         if (self.breakpoints.contains(name) || self.is_a_step) && self.is_debugging {
             debugger::debug(self, name);
         }
 
+        if let Some(builtin) = Builtin::get_for_name(name) {
+            self.call_builtin(&builtin, arguments)
+        } else {
+            self.call_defined_function(name, arguments)
+        }
+    }
+
+    /// Calls a function using property syntax, like (object.method 1). If the object is a host object with this method, the method is called instead of a function.
+    /// If the object is a table with a function in this field (or in its prototypes), the function is called with the object as the first argument (self).
+    pub(crate) fn call_method(&mut self, name: &String, mut arguments: Vec<Value>) -> Result<Value, HierError> {
+        if let Some(Value::USER_DATA(object)) = arguments.first() {
+            if object.has_method(name) {
+                let object = object.clone();
+                arguments.remove(0);
+
+                self.call_stack.push(Call::new(format!("{}.{}", object.type_name(), name), None, self.current_interpreting_location.clone()));
+                let result = object.call_method(self, name, arguments).and_then(|value| self.check_size(value));
+                self.call_stack.pop();
+                return result;
+            }
+        }
+
+        if let Some(Value::TABLE(table)) = arguments.first() {
+            if let Some(method @ (Value::FUNCTION(_) | Value::NATIVE_FUNCTION(_, _))) = find_field(table, name) {
+                let method = method.clone();
+                return self.call_value(name, method, arguments);
+            }
+        }
+
+        self.call_function(name, arguments)
+    }
+
+    pub(crate) fn call_builtin(&mut self, builtin: &Builtin, arguments: Vec<Value>) -> Result<Value, HierError> {
+        self.call_stack.push(Call::new(builtin.name(), None, self.current_interpreting_location.clone()));
+        let result = self.run_builtin(builtin, arguments).and_then(|value| self.check_size(value));
+        self.call_stack.pop();
+        result
+    }
+
+    fn run_builtin(&mut self, builtin: &Builtin, arguments: Vec<Value>) -> Result<Value, HierError> {
+        match builtin {
+            Builtin::GET => self.call_get(arguments),
+            Builtin::IMPORT => self.call_import(arguments),
+            Builtin::LIST => self.call_list(arguments),
+            Builtin::ADDITION => self.call_addition(arguments),
+            Builtin::SUBTRACTION => self.call_subtraction(arguments),
+            Builtin::MULTIPLICATION => self.call_multiplication(arguments),
+            Builtin::DIVISION => self.call_division(arguments),
+            Builtin::NEGATE => self.call_negate(arguments),
+            Builtin::AND | Builtin::OR => self.call_logical(builtin.name(), arguments),
+            Builtin::EQUAL | Builtin::NOT_EQUAL | Builtin::LESS_EQUAL | Builtin::GREATER_EQUAL | Builtin::LESS | Builtin::GREATER => self.call_comparison(builtin.name(), arguments),
+            Builtin::NULL_COALESCING => self.call_null_coalescing(arguments),
+            Builtin::APPEND => self.call_append(arguments),
+            Builtin::BRPOINT => self.call_brpoint(arguments),
+            Builtin::MODULO => self.call_modulo(arguments),
+            Builtin::INTEGER_DIVISION => self.call_integer_division(arguments),
+            Builtin::IS => self.call_is(arguments),
+            Builtin::PRINT => self.call_print(arguments),
+            Builtin::PRINTLN => self.call_println(arguments),
+            Builtin::EVAL => self.call_eval(arguments),
+            Builtin::BREAK => self.call_break(arguments),
+            Builtin::CONTINUE => self.call_continue(arguments),
+            Builtin::STACKTRACE => self.call_stacktrace(arguments),
+            Builtin::RETURN => self.call_return(arguments),
+            Builtin::ERROR => self.call_error(arguments),
+            Builtin::PANIC => self.call_panic(arguments),
+            Builtin::READ => self.call_read(arguments),
+            Builtin::INSERT => self.call_insert(arguments),
+            Builtin::ROUND => self.call_round(arguments),
+            Builtin::MAP => self.call_map(arguments),
+            Builtin::REMOVE => self.call_remove(arguments),
+            Builtin::REPLACE => self.call_replace(arguments),
+            Builtin::LENGTH => self.call_length(arguments),
+            Builtin::GRAPHEMES => self.call_graphemes(arguments),
+            Builtin::STRING => self.call_string(arguments),
+            Builtin::NUMBER => self.call_number(arguments),
+            Builtin::IF => self.call_if(arguments),
+            Builtin::WHILE => self.call_while(arguments),
+            Builtin::TABLE => self.call_table(arguments),
+            Builtin::NEW => self.call_new(arguments),
+            Builtin::SET => self.call_set(arguments),
+            Builtin::DELETE => self.call_delete(arguments),
+            Builtin::HAS => self.call_has(arguments),
+            Builtin::KEYS => self.call_keys(arguments),
+            Builtin::VALUES => self.call_values(arguments),
+            Builtin::MERGE => self.call_merge(arguments),
+            Builtin::RECORD => self.call_record(arguments),
+            Builtin::REPEAT => self.call_repeat(arguments),
+            Builtin::FOR => self.call_for(arguments),
+            Builtin::RUN => self.call_run(arguments),
+            Builtin::TRY => self.call_try(arguments),
+            Builtin::MATCH => self.call_match(arguments),
+        }
+    }
+
+    /// Calls a function which isn't a builtin: a function from a module, a declaration, an assignment or a user-defined function.
+    pub(crate) fn call_defined_function(&mut self, name: &String, arguments: Vec<Value>) -> Result<Value, HierError> {
         if name.contains("::") {
             let path = name.split_once("::").unwrap();
 
-            if path.1.to_string().chars().collect::<Vec<char>>()[0] == '_' {
-                return Value::NULL;
+            if path.1.is_empty() {
+                return self.error(&format!("Name of a member of {} can't be empty.", path.0));
             }
 
-            let environment = self.get(path.0.to_string());
+            if path.1.starts_with('_') {
+                return Ok(Value::NULL);
+            }
+
+            let environment = self.get(path.0.to_string())?;
 
             if let Value::ENVIRONMENT(target_environment) = environment {
                 let mut environment = target_environment.clone();
 
                 environment.is_a_step = environment.should_step_into;
+                // Function from the module is a part of this call stack and is called from here.
+                environment.call_stack = self.call_stack.clone();
+                environment.current_interpreting_location = self.current_interpreting_location.clone();
 
-                let result = environment.call_function(&path.1.to_string(), arguments);
+                let result = environment.call_function(&path.1.to_string(), arguments)?;
 
-                self.assign(path.0.to_string(), Value::ENVIRONMENT(environment));
+                self.assign(path.0.to_string(), Value::ENVIRONMENT(environment))?;
 
-                result
+                Ok(result)
             } else if let Value::NULL = environment {
-                return Value::NULL;
+                Ok(Value::NULL)
             } else {
                 let target = path.0;
-                self.error(&format!("{target} is not an environment."));
+                self.error(&format!("{target} is not an environment."))
             }
-        } else {
-            match &name as &str {
-                "get" => self.call_get(arguments),
-                "import" | "load" => self.call_import(arguments),
-                "&" | "list" => self.call_list(arguments),
-                "+" => self.call_addition(arguments),
-                "-" => self.call_subtraction(arguments),
-                "*" => self.call_multiplication(arguments),
-                "/" => self.call_division(arguments),
-                "!" => self.call_negate(arguments),
-                "&&" | "||" => self.call_logical(name, arguments),
-                "==" | "!=" | "<=" | ">=" | "<" | ">" => self.call_comparison(name, arguments),
-                "??" => self.call_null_coalescing(arguments),
-                "append" => self.call_append(arguments),
-                "brpoint" => self.call_brpoint(arguments),
-                "%" => self.call_modulo(arguments),
-                "is" => self.call_is(arguments),
-                "print" => self.call_print(arguments),
-                "println" => self.call_println(arguments),
-                "eval" => self.call_eval(arguments),
-                "break" => self.call_break(arguments),
-                "error" => self.call_error(arguments),
-                "panic" => self.call_panic(arguments),
-                "read" => self.call_read(arguments),
-                "insert" => self.call_insert(arguments),
-                "round" => self.call_round(arguments),
-                "map" => self.call_map(arguments),
-                "remove" => self.call_remove(arguments),
-                "replace" => self.call_replace(arguments),
-                "length" => self.call_length(arguments),
-                "string" => self.call_string(arguments),
-                "number" => self.call_number(arguments),
-                "if" => self.call_if(arguments),
-                "while" => self.call_while(arguments),
-                "table" | "#" => self.call_table(arguments),
-                "repeat" => self.call_repeat(arguments),
-                "for" => self.call_for(arguments),
-                "run" => self.call_run(arguments),
-                "try" => self.call_try(arguments),
-                _ => {
-                    if name.chars().nth(0).unwrap_or(' ') == '@' {
-                        // Process declaration
-                        if name == "@" {
-                            self.error("Name can't be empty (can't be only @).")
-                        }
-
-                        let mut name = name.clone();
-                        name.remove(0);
-
-                        if 2 < arguments.len() {
-                            self.declare(name, Value::LIST(arguments.clone()));
-                            Value::LIST(arguments)
-                        } else if arguments.len() == 2 {
-                            if let Value::FUNCTION_ARGUMENTS(parameters) = arguments[0].clone() {
-                                if let Value::BLOCK(block) = arguments[1].clone() {
-                                    self.declare(name, Value::FUNCTION(parameters.clone(), Box::new(Value::BLOCK(block.clone()))));
-                                    Value::FUNCTION(parameters, Box::new(Value::BLOCK(block)))
-                                } else {
-                                    self.error("Function definition's second argument must be a block.");
-                                }
-                            } else {
-                                self.error("Function definition's first argument must be function arguments.");
-                            }
-                        } else if arguments.len() == 1 {
-                            self.declare(name, arguments[0].clone());
-                            arguments[0].clone()
-                        } else {
-                            self.error("Variable set operation must have 1 or more arguments.");
-                        }
-                    } else if name.chars().nth(0).unwrap_or(' ') == '=' {
-                        // Process assignment
-                        if name == "=" {
-                            self.error("Name can't be empty (can't be only =).")
-                        }
-
-                        let mut name = name.clone();
-                        name.remove(0);
-
-                        if 2 < arguments.len() {
-                            self.assign(name, Value::LIST(arguments.clone()));
-                            Value::LIST(arguments)
-                        } else if arguments.len() == 2 {
-                            if let Value::FUNCTION_ARGUMENTS(parameters) = arguments[0].clone() {
-                                if let Value::BLOCK(block) = arguments[1].clone() {
-                                    self.assign(name, Value::FUNCTION(parameters.clone(), Box::new(Value::BLOCK(block.clone()))));
-                                    Value::FUNCTION(parameters, Box::new(Value::BLOCK(block)))
-                                } else {
-                                    self.error("Function definition's second argument must be a block.");
-                                }
-                            } else {
-                                self.error("Function definition's first argument must be function arguments.");
-                            }
-                        } else if arguments.len() == 1 {
-                            self.assign(name, arguments[0].clone());
-                            arguments[0].clone()
-                        } else {
-                            self.error("Variable set operation must have 1 or more arguments.");
-                        }
+        } else if name.chars().nth(0).unwrap_or(' ') == '@' {
+            // Process declaration
+            if name == "@" {
+                return match arguments.first() {
+                    Some(Value::PATTERN(_)) => self.destructure(arguments, true),
+                    _ => self.error("Name can't be empty (can't be only @).")
+                };
+            }
+
+            let mut name = name.clone();
+            name.remove(0);
+
+            if 2 < arguments.len() {
+                self.declare(name, Value::LIST(arguments.clone().into()))?;
+                Ok(Value::LIST(arguments.into()))
+            } else if arguments.len() == 2 {
+                if let Value::FUNCTION_ARGUMENTS(parameters) = arguments[0].clone() {
+                    if let Value::BLOCK(block) = arguments[1].clone() {
+                        let function = self.make_function(parameters, block);
+                        self.declare(name, function.clone())?;
+                        Ok(function)
                     } else {
-                        self.call_user_defined_function(name, arguments)
+                        self.error("Function definition's second argument must be a block.")
                     }
+                } else {
+                    self.error("Function definition's first argument must be function arguments.")
                 }
+            } else if arguments.len() == 1 {
+                self.declare(name, arguments[0].clone())?;
+                Ok(arguments[0].clone())
+            } else {
+                self.error("Variable set operation must have 1 or more arguments.")
+            }
+        } else if name.chars().nth(0).unwrap_or(' ') == '=' {
+            // Process assignment
+            if name == "=" {
+                return match arguments.first() {
+                    Some(Value::PATTERN(_)) => self.destructure(arguments, false),
+                    _ => self.error("Name can't be empty (can't be only =).")
+                };
             }
+
+            let mut name = name.clone();
+            name.remove(0);
+
+            if 2 < arguments.len() {
+                self.assign(name, Value::LIST(arguments.clone().into()))?;
+                Ok(Value::LIST(arguments.into()))
+            } else if arguments.len() == 2 {
+                if let Value::FUNCTION_ARGUMENTS(parameters) = arguments[0].clone() {
+                    if let Value::BLOCK(block) = arguments[1].clone() {
+                        let function = self.make_function(parameters, block);
+                        self.assign(name, function.clone())?;
+                        Ok(function)
+                    } else {
+                        self.error("Function definition's second argument must be a block.")
+                    }
+                } else {
+                    self.error("Function definition's first argument must be function arguments.")
+                }
+            } else if arguments.len() == 1 {
+                self.assign(name, arguments[0].clone())?;
+                Ok(arguments[0].clone())
+            } else {
+                self.error("Variable set operation must have 1 or more arguments.")
+            }
+        } else {
+            self.call_user_defined_function(name, arguments)
         }
     }
-}
\ No newline at end of file
+}
+
+/// Directory of a module with given path (ending with /). Relative paths are resolved from the current directory.
+pub(crate) fn module_directory(path: &str) -> String {
+    let mut directory = path.to_string();
+
+    if directory.starts_with("./") {
+        directory.remove(0);
+        directory.remove(0);
+        directory = std::env::current_dir().unwrap().to_str().unwrap().to_string() + "/" + &directory;
+    }
+
+    if !directory.ends_with('/') {
+        let mut directory_split = directory.split('/').collect::<Vec<&str>>();
+        directory_split.remove(directory_split.len() - 1);
+        directory = directory_split.join("/");
+        directory += "/"
+    }
+
+    directory
+}
@@ -0,0 +1,51 @@
+use crate::hier::call::Call;
+use crate::hier::location::Location;
+use crate::hier::value::Value;
+
+/// Error which can be handled by the code. It remembers where it was created and what was called at that moment.
+#[derive(Debug, Clone)]
+pub struct ErrorValue {
+    pub kind: String,
+    pub message: String,
+    pub location: Location,
+    pub trace: Vec<Call>,
+    pub payload: Option<Value>,
+    pub cause: Option<Value>
+}
+
+impl ErrorValue {
+    pub fn new(kind: String, message: String, location: Location, trace: Vec<Call>) -> Self {
+        Self {
+            kind,
+            message,
+            location,
+            trace,
+            payload: None,
+            cause: None
+        }
+    }
+
+    /// Value of a property accessible from the code (error.message etc.).
+    pub fn get_property(&self, property: &str) -> Value {
+        match property {
+            "kind" => Value::STRING(self.kind.as_str().into()),
+            "message" => Value::STRING(self.message.as_str().into()),
+            "location" => Value::STRING(self.location.text_representation().into()),
+            // The innermost call is the first one.
+            "trace" => Value::LIST(self.trace.iter().rev().map(|call| Value::STRING(call.text_representation().into())).collect()),
+            "payload" => self.payload.clone().unwrap_or(Value::NULL),
+            "cause" => self.cause.clone().unwrap_or(Value::NULL),
+            _ => Value::NULL
+        }
+    }
+
+    pub fn text_representation(&self) -> String {
+        let mut text = format!("{}: {} ({})", self.kind, self.message, self.location.text_representation());
+
+        if let Some(cause) = &self.cause {
+            text += &format!("\nCaused by {}", cause.text_representation());
+        }
+
+        text
+    }
+}
@@ -1,12 +1,18 @@
+use std::rc::Rc;
+use bigdecimal::BigDecimal;
+use num_bigint::BigInt;
 use crate::hier::interpolated_string::InterpolatedString;
 use crate::hier::location::Location;
 use crate::hier::value::Value;
 
-#[allow(non_camel_case_types)]
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
 #[derive(Debug, Clone)]
 pub enum Expression {
     STRING(InterpolatedString, Location),
     NUMBER(f64, Location),
+    INTEGER(i64, Location),
+    BIG_INTEGER(Rc<BigInt>, Location),
+    DECIMAL(Rc<BigDecimal>, Location),
     IDENTIFIER(String, Location),
     LIST(Vec<Expression>, Location),
     BLOCK(Vec<Expression>, Location),
@@ -20,6 +26,9 @@ impl Expression {
         match self {
             Expression::STRING(_, location) => location.clone(),
             Expression::NUMBER(_, location) => location.clone(),
+            Expression::INTEGER(_, location) => location.clone(),
+            Expression::BIG_INTEGER(_, location) => location.clone(),
+            Expression::DECIMAL(_, location) => location.clone(),
             Expression::IDENTIFIER(_, location) => location.clone(),
             Expression::LIST(_, location) => location.clone(),
             Expression::BLOCK(_, location) => location.clone(),
@@ -33,6 +42,9 @@ impl Expression {
         match self {
             Expression::STRING(value, _) => "\"".to_string() + &*(value.raw.clone()) + "\"",
             Expression::NUMBER(value, _) => value.to_string().clone(),
+            Expression::INTEGER(value, _) => value.to_string(),
+            Expression::BIG_INTEGER(value, _) => value.to_string() + "n",
+            Expression::DECIMAL(value, _) => value.to_plain_string() + "d",
             Expression::IDENTIFIER(value, _) => value.clone(),
             Expression::PROPERTY(key, property, _) => key.get_representation() + "." + &*(property.clone()),
             Expression::VALUE(value) => value.text_representation().clone(),
@@ -41,7 +53,7 @@ impl Expression {
                 let mut result = String::from("(");
                 let mut i = 0;
                 for expression in expressions {
-                    result.push_str(&*(expression.get_representation()));
+                    result.push_str(&(expression.get_representation()));
 
                     if i < expressions.len() - 1 {
                         result += " ";
@@ -49,18 +61,18 @@ impl Expression {
                     }
                 }
 
-                result.push_str(")");
+                result.push(')');
                 result
             },
             Expression::BLOCK(expressions, _) => {
                 let mut result = String::from("{ ");
 
                 for expression in expressions {
-                    result.push_str(&*(expression.get_representation()));
+                    result.push_str(&(expression.get_representation()));
                     result += " ";
                 }
 
-                result.push_str("}");
+                result.push('}');
                 result
             }
         }
@@ -0,0 +1,39 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use crate::hier::value::Value;
+
+/// Variables of one scope. Frames are linked to the frame they are nested in, so entering and leaving a scope only changes the current frame. Functions keep the frame they were defined in alive.
+pub struct Frame {
+    pub values: HashMap<String, Value>,
+    pub parent: Option<Rc<RefCell<Frame>>>
+}
+
+impl Frame {
+    pub fn new(parent: Option<Rc<RefCell<Frame>>>) -> Rc<RefCell<Frame>> {
+        Rc::new(RefCell::new(Self {
+            values: HashMap::new(),
+            parent
+        }))
+    }
+
+    /// Finds a variable in this frame or frames it is nested in.
+    pub fn get(&self, key: &str) -> Option<Value> {
+        match self.values.get(key) {
+            Some(value) => Some(value.clone()),
+            None => self.parent.as_ref().and_then(|parent| parent.borrow().get(key))
+        }
+    }
+
+    /// Assigns a value to a variable in this frame or frames it is nested in. Returns the value back if the variable wasn't found.
+    pub fn assign(&mut self, key: &str, value: Value) -> Option<Value> {
+        if let Some(variable) = self.values.get_mut(key) {
+            *variable = value;
+            None
+        } else if let Some(parent) = &self.parent {
+            parent.borrow_mut().assign(key, value)
+        } else {
+            Some(value)
+        }
+    }
+}
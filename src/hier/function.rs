@@ -0,0 +1,24 @@
+use std::cell::RefCell;
+use std::fmt::{Debug, Formatter};
+use std::rc::{Rc, Weak};
+use crate::hier::block::Block;
+use crate::hier::environment::Globals;
+use crate::hier::frame::Frame;
+
+/// Function defined by the code. It's a closure: it sees variables of the scope and the module in which it was defined, wherever it's called.
+pub struct Function {
+    pub(crate) parameters: Vec<String>,
+    pub(crate) block: Block,
+    /// Frame of the scope in which the function was defined. None if it was defined in the global scope.
+    pub(crate) frame: Option<Rc<RefCell<Frame>>>,
+    /// Globals of the module in which the function was defined. They are owned by the module, so functions stored in globals don't keep them alive forever.
+    pub(crate) globals: Weak<RefCell<Globals>>,
+    /// Path of the module in which the function was defined.
+    pub(crate) module: String
+}
+
+impl Debug for Function {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Function {{ parameters: {:?}, block: {:?} }}", self.parameters, self.block)
+    }
+}
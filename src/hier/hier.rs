@@ -1,27 +1,89 @@
+use std::cell::RefCell;
+use std::fs;
+use std::rc::Rc;
+use crate::hier::backend::Backend;
+use crate::hier::conversion::IntoNativeFunction;
+use crate::hier::block::Block;
 use crate::hier::debugger;
-use crate::hier::environment::{Environment, VariableId};
+use crate::hier::environment::{Environment, module_directory};
+use crate::hier::expression::Expression;
+use crate::hier::hier_error::HierError;
+use crate::hier::location::Location;
 use crate::hier::parser::Parser;
+use crate::hier::cancellation::CancelHandle;
+use crate::hier::limits::{Budget, Limits};
+use crate::hier::policy::Policy;
 use crate::hier::tokenizer::Tokenizer;
 use crate::hier::value::Value;
 
+/// Hier interpreter, which can be embedded in other programs.
+///
+/// Globals (including functions and variables added by the host) are kept between runs, so code can be run in parts.
+/// Module reader is used to read files of imported modules. If it fails, it returns a message, which becomes an error of the import. Exit handler is called when the code has to be ended immediately, for example by the debugger.
+/// Both of them, as well as native functions, can be closures which capture state of the host.
+///
+/// ```
+/// use hier::{Hier, Policy, Value};
+///
+/// let mut hier = Hier::new("./main".to_string(), |path| std::fs::read_to_string(path).map_err(|error| error.to_string()), || std::process::exit(0), false, Policy::default());
+/// hier.add_variable("answer".to_string(), Value::NUMBER(42.0));
+///
+/// let value = hier.run("(+ answer 1)".to_string()).unwrap();
+/// assert_eq!(value.text_representation(), "43");
+/// ```
 pub struct Hier {
     environment: Environment,
-    module_reader: fn(String) -> String,
-    exit_handler: fn() -> !,
     pub debug: bool
 }
 
 impl Hier {
-    pub fn new(path: String, module_reader: fn(String) -> String, exit_handler: fn() -> !, debug: bool) -> Self {
+    /// Creates an interpreter for a module with given path. Relative imports are resolved from its directory.
+    /// Policy decides which operations (like reading files or running processes) the code can do.
+    pub fn new(path: String, module_reader: impl Fn(String) -> Result<String, String> + 'static, exit_handler: impl Fn() + 'static, debug: bool, policy: Policy) -> Self {
+        let mut environment = Environment::new(false, path, Rc::new(module_reader), Rc::new(exit_handler), debug, vec![]);
+        environment.policy = Rc::new(policy);
+
         Self {
-            environment: Environment::new(false, path, module_reader, exit_handler, debug, vec![]),
-            module_reader,
-            exit_handler,
+            environment,
             debug
         }
     }
 
-    pub fn run(&mut self, code: String) -> Value {
+    /// Creates an interpreter for REPL. It allows redeclaring variables and should be used with run_line.
+    pub fn new_repl(module_reader: impl Fn(String) -> Result<String, String> + 'static, exit_handler: impl Fn() + 'static, policy: Policy) -> Self {
+        let mut environment = Environment::new(true, "./repl".to_string(), Rc::new(module_reader), Rc::new(exit_handler), false, vec![]);
+        environment.policy = Rc::new(policy);
+
+        Self {
+            environment,
+            debug: false
+        }
+    }
+
+    /// Creates an interpreter for code run by other code (like eval). It has the same handlers, policy and root as the code running it, but not its variables.
+    pub(crate) fn nested(parent: &Environment) -> Self {
+        let mut environment = Environment::new(false, parent.path.clone(), parent.module_reader.clone(), parent.exit_handler.clone(), false, vec![]);
+        environment.backend = parent.backend;
+        environment.policy = parent.policy.clone();
+        environment.root = parent.root.clone();
+        environment.budget = parent.budget.clone();
+        environment.cancellation = parent.cancellation.clone();
+
+        Self {
+            environment,
+            debug: false
+        }
+    }
+
+    /// Runs the code and returns value of its last expression or an error which stopped it.
+    /// Globals declared by previous runs are available, so a program can be run in parts. Every run has its own limits.
+    pub fn run(&mut self, code: String) -> Result<Value, HierError> {
+        self.environment.start_run();
+        self.run_code(code)
+    }
+
+    /// Runs the code without starting a new budget, so code run by other code (like eval) counts to its limits.
+    pub(crate) fn run_code(&mut self, code: String) -> Result<Value, HierError> {
         let mut code = code;
 
         if !code.starts_with('(') {
@@ -32,15 +94,13 @@ impl Hier {
         let mut tokenizer = Tokenizer::new(code);
 
         if tokenizer.tokenize_module() {
-            println!("Failed.");
-            (self.exit_handler)();
+            return Err(first_error(tokenizer.errors));
         }
 
-        let mut parser = Parser::new(tokenizer.tokens, self.module_reader, self.exit_handler);
+        let mut parser = Parser::new(tokenizer.tokens);
 
         if parser.parse() {
-            println!("Failed.");
-            (self.exit_handler)();
+            return Err(first_error(parser.errors));
         }
 
         self.environment.code = parser.code;
@@ -49,18 +109,156 @@ impl Hier {
             debugger::debug(&mut self.environment, &String::new());
         }
 
-        self.environment.interpret()
+        let result = self.environment.interpret();
+        self.recover(result)
+    }
+
+    /// Returns value of a global variable (including functions declared by the code), if it exists.
+    pub fn get_global(&self, name: &str) -> Option<Value> {
+        self.environment.globals.borrow().get(name).cloned()
+    }
+
+    /// Calls a function value (user-defined or native), for example one returned by get_global. Arguments can be made using IntoValue.
+    pub fn call(&mut self, function: &Value, arguments: Vec<Value>) -> Result<Value, HierError> {
+        self.call_named("function", function.clone(), arguments)
+    }
+
+    /// Calls a global function with given name.
+    pub fn call_global(&mut self, name: &str, arguments: Vec<Value>) -> Result<Value, HierError> {
+        match self.get_global(name) {
+            Some(function) => self.call_named(name, function, arguments),
+            None => Err(HierError::new(format!("Function {} doesn't exist.", name), Location::empty(), vec![]))
+        }
+    }
+
+    fn call_named(&mut self, name: &str, function: Value, arguments: Vec<Value>) -> Result<Value, HierError> {
+        if !matches!(function, Value::FUNCTION(_) | Value::NATIVE_FUNCTION(_, _)) {
+            return Err(HierError::new(format!("{} is not a function, but {}.", name, function.get_type().text_representation()), Location::empty(), vec![]));
+        }
+
+        // Call comes from the host, not from any place in the code.
+        self.environment.current_interpreting_location = Location::new("host".to_string(), 0, 0);
+        self.environment.start_run();
+
+        let result = self.environment.call_value(name, function, arguments);
+        self.recover(result)
+    }
+
+    /// Error could have stopped the code in the middle of a function or a loop, so its state is cleared.
+    fn recover(&mut self, result: Result<Value, HierError>) -> Result<Value, HierError> {
+        if result.is_err() {
+            self.environment.frame = None;
+            self.environment.call_stack.clear();
+            self.environment.control_flow = None;
+        }
+
+        result
     }
 
-    pub fn add_function(&mut self, name: String, arguments_count: i64, function: fn(&mut Environment, Vec<Value>) -> Value) {
+    /// Reads a file and runs it as the main module. Relative imports are resolved from the file's directory.
+    pub fn run_file(&mut self, path: &str) -> Result<Value, HierError> {
+        let (full_path, contents) = match fs::canonicalize(path).and_then(|full_path| Ok((full_path.clone(), fs::read_to_string(full_path)?))) {
+            Ok(file) => file,
+            Err(error) => return Err(HierError::new(format!("Unable to read the file {}: {}.", path, error), Location::empty(), vec![]))
+        };
+
+        self.environment.path = full_path.to_string_lossy().to_string();
+        self.environment.root = module_directory(&self.environment.path);
+        self.run(contents)
+    }
+
+    /// Runs a single line of REPL. Unlike run, it keeps nothing from the line if it fails.
+    pub fn run_line(&mut self, line: String) -> Result<Value, HierError> {
+        let mut tokenizer = Tokenizer::new(line);
+
+        tokenizer.module_name = "REPL".to_string();
+
+        if tokenizer.tokenize_code() {
+            return Err(first_error(tokenizer.errors));
+        }
+
+        let mut parser = Parser::new(tokenizer.tokens);
+
+        if parser.parse() {
+            return Err(first_error(parser.errors));
+        }
+
+        let code = if let Expression::BLOCK(code, _) = parser.code {
+            code
+        } else {
+            vec![parser.code]
+        };
+
+        // Line runs on a copy, so nothing is left in a broken state when it fails. Globals are shared with functions, so they are restored in place.
+        let mut environment = self.environment.clone();
+        let globals = self.environment.globals.borrow().clone();
+        environment.start_run();
+
+        match environment.interpret_top_level(Block::new(code)) {
+            Ok(value) => {
+                self.environment = environment;
+                Ok(value)
+            },
+            Err(error) => {
+                *self.environment.globals.borrow_mut() = globals;
+                Err(error)
+            }
+        }
+    }
+
+    /// Selects how the code is executed. Bytecode VM is used by default.
+    pub fn set_backend(&mut self, backend: Backend) {
+        self.environment.backend = backend;
+    }
+
+    /// Returns a handle which stops the running code from another thread (or a signal handler), without losing globals.
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.environment.cancellation.clone()
+    }
+
+    /// Sets resources which every run can use. Code which exceeds them is stopped with an error of kind LIMIT.
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.environment.budget = Rc::new(Budget::new(limits));
+    }
+
+    /// Adds a typed function implemented in Rust, like fn(String, f64) -> bool. Arguments are converted using FromValue and the result using IntoResult.
+    /// Number and types of arguments are checked automatically. The function can take the environment as its first argument.
+    pub fn add_function<Arguments>(&mut self, name: String, function: impl IntoNativeFunction<Arguments>) {
+        let (function, arguments_count) = function.into_native_function(name.clone());
+        self.environment.globals.borrow_mut().insert(name, Value::NATIVE_FUNCTION(function, arguments_count));
+    }
+
+    /// Adds a function implemented in Rust, which gets arguments as values. Arguments count is checked before calling it, unless it's -1 (any number of arguments).
+    /// It can be a closure. To keep a state which changes, capture a Cell or a RefCell or use add_native_function_mut.
+    pub fn add_native_function(&mut self, name: String, arguments_count: i64, function: impl Fn(&mut Environment, Vec<Value>) -> Result<Value, HierError> + 'static) {
         if arguments_count < -1 {
             panic!("Invalid argument count for function {}. Must be either -1 (infinite) or 0 and higher.", name);
         }
 
-        self.environment.values.insert(VariableId(0, name), Value::NATIVE_FUNCTION(function, arguments_count));
+        self.environment.globals.borrow_mut().insert(name, Value::NATIVE_FUNCTION(Rc::new(function), arguments_count));
     }
 
+    /// Adds a function implemented in Rust, which can mutate its captured state. Calling it while it's running (for example, from Hier code called by it) is an error.
+    pub fn add_native_function_mut(&mut self, name: String, arguments_count: i64, function: impl FnMut(&mut Environment, Vec<Value>) -> Result<Value, HierError> + 'static) {
+        let function = RefCell::new(function);
+        let function_name = name.clone();
+
+        self.add_native_function(name, arguments_count, move |environment, arguments| {
+            if let Ok(mut function) = function.try_borrow_mut() {
+                function(environment, arguments)
+            } else {
+                environment.error(&format!("Function {} can't be called while it's running.", function_name))
+            }
+        });
+    }
+
+    /// Adds a global variable.
     pub fn add_variable(&mut self, name: String, value: Value) {
-        self.environment.values.insert(VariableId(0, name), value);
+        self.environment.globals.borrow_mut().insert(name, value);
     }
+}
+
+/// Syntax error which is returned to the host. Later errors are often caused by the first one, so only it is returned.
+fn first_error(errors: Vec<HierError>) -> HierError {
+    errors.into_iter().next().unwrap_or_else(|| HierError::new("Failed to parse the code.".to_string(), Location::empty(), vec![]))
 }
\ No newline at end of file
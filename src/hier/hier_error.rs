@@ -0,0 +1,59 @@
+use std::fmt::{Display, Formatter};
+use crate::hier::call::Call;
+use crate::hier::limits::Limit;
+use crate::hier::location::Location;
+
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HierErrorKind {
+    /// Error in the code, like calling a function with wrong arguments.
+    ERROR,
+    /// Code used more resources than its limits allow.
+    LIMIT(Limit),
+    /// Code was stopped by the host using a CancelHandle.
+    CANCELLED
+}
+
+/// Error which stops execution of the code. Unlike error values, it can't be handled by the code and is returned to the host.
+#[derive(Debug, Clone)]
+pub struct HierError {
+    pub kind: HierErrorKind,
+    pub message: String,
+    pub location: Location,
+    /// Calls which were running when the error happened. The innermost call is the last one.
+    pub trace: Vec<Call>
+}
+
+impl HierError {
+    pub fn new(message: String, location: Location, trace: Vec<Call>) -> Self {
+        Self {
+            kind: HierErrorKind::ERROR,
+            message,
+            location,
+            trace
+        }
+    }
+}
+
+impl Display for HierError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // Errors found before running the code, like syntax errors, have no location.
+        if self.location.module.is_empty() {
+            write!(f, "!: {}", self.message)?;
+        } else {
+            write!(f, "({}:{} in {}) !: {}", self.location.line_number, self.location.offset, self.location.module, self.message)?;
+        }
+
+        if !self.trace.is_empty() {
+            write!(f, "\nBacktrace:")?;
+
+            for call in self.trace.iter().rev() {
+                write!(f, "\n  {}", call.text_representation())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for HierError {}
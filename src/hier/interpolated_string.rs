@@ -1,49 +1,48 @@
-use crate::hier::expression::Expression;
+use crate::hier::block::Block;
 use crate::hier::{Location, report};
 use crate::hier::environment::Environment;
+use crate::hier::hier_error::HierError;
 use crate::hier::parser::Parser;
 use crate::hier::tokenizer::Tokenizer;
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, Clone)]
 enum InterpolatedStringPart {
     RAW(String),
-    EXPRESSION(Expression)
+    EXPRESSION(Block)
 }
 
 #[derive(Debug, Clone)]
 pub struct InterpolatedString {
     parts: Vec<InterpolatedStringPart>,
     pub raw: String,
-    current_index: usize,
-    pub module_reader: fn(String) -> String,
-    pub exit_handler: fn() -> !
-
+    current_index: usize
 }
 
 impl InterpolatedString {
-    pub fn construct(string: String, location: Location, module_reader: fn(String) -> String, exit_handler: fn() -> !) -> InterpolatedString {
-        let mut string = Self::new(string, module_reader, exit_handler);
+    pub fn construct(string: String, location: Location) -> InterpolatedString {
+        let mut string = Self::new(string);
 
         string.parse(location);
 
         string
     }
 
-    pub fn new(string: String, module_reader: fn(String) -> String, exit_handler: fn() -> !) -> Self {
+    pub fn new(string: String) -> Self {
         Self {
             parts: vec![],
             raw: string,
-            current_index: 0,
-            module_reader,
-            exit_handler
+            current_index: 0
         }
     }
 
     pub fn parse(&mut self, location: Location) {
         let mut will_interpolate = false;
         let mut raw_part = String::new();
+        // Indices count characters, not bytes.
+        let length = self.raw.chars().count();
 
-        while self.current_index < self.raw.len() {
+        while self.current_index < length {
             let current_char = self.peek();
 
             if current_char == '\\' {
@@ -53,16 +52,22 @@ impl InterpolatedString {
                 self.parts.push(InterpolatedStringPart::RAW(raw_part));
                 raw_part = String::new();
 
-                let mut tokenizer = Tokenizer::new(self.raw[self.current_index..].to_string());
+                let mut tokenizer = Tokenizer::new(self.raw.chars().skip(self.current_index).collect());
 
                 let offset = tokenizer.tokenize_interpolation();
                 self.current_index += offset;
 
-                let mut parser = Parser::new(tokenizer.tokens, self.module_reader, self.exit_handler);
+                let mut parser = Parser::new(tokenizer.tokens);
 
-                parser.parse();
+                // Errors inside of strings don't stop the code.
+                if parser.parse() || !tokenizer.errors.is_empty() {
+                    for error in tokenizer.errors.iter().chain(&parser.errors) {
+                        report(&error.message, error.location.clone());
+                    }
+                }
 
-                self.parts.push(InterpolatedStringPart::EXPRESSION(parser.code))
+                self.parts.push(InterpolatedStringPart::EXPRESSION(Block::new(vec![parser.code])));
+                will_interpolate = false;
             } else if will_interpolate {
                 match current_char {
                     'n' => raw_part.push('\n'),
@@ -72,6 +77,7 @@ impl InterpolatedString {
                 }
 
                 self.consume();
+                will_interpolate = false;
             } else {
                 raw_part.push(current_char);
                 self.consume();
@@ -93,16 +99,30 @@ impl InterpolatedString {
         char
     }
 
-    pub fn resolve(&self, environment: &mut Environment) -> String {
+    /// Text of the string if it isn't interpolated.
+    pub fn as_plain(&self) -> Option<String> {
+        let mut text = String::new();
+
+        for part in &self.parts {
+            match part {
+                InterpolatedStringPart::RAW(raw) => text.push_str(raw),
+                InterpolatedStringPart::EXPRESSION(_) => return None
+            }
+        }
+
+        Some(text)
+    }
+
+    pub fn resolve(&self, environment: &mut Environment) -> Result<String, HierError> {
         let mut resolved = String::new();
 
-        for part in self.parts.clone() {
+        for part in &self.parts {
             match part {
-                InterpolatedStringPart::RAW(raw) => resolved.push_str(&raw),
-                InterpolatedStringPart::EXPRESSION(expression) => resolved.push_str(&environment.interpret_block(vec![expression]).text_representation())
+                InterpolatedStringPart::RAW(raw) => resolved.push_str(raw),
+                InterpolatedStringPart::EXPRESSION(block) => resolved.push_str(&environment.interpret_block(block.clone())?.text_representation())
             }
         }
 
-        resolved
+        Ok(resolved)
     }
 }
\ No newline at end of file
@@ -1,44 +1,80 @@
+use crate::hier::backend::Backend;
+use crate::hier::block::Block;
+use crate::hier::control_flow::ControlFlow;
 use crate::hier::environment::Environment;
 use crate::hier::expression::Expression;
+use crate::hier::hier_error::HierError;
 use crate::hier::types::Type;
-use crate::hier::value::Value;
+use crate::hier::value::{List, Value};
 
 pub fn warning(error: &str) {
     eprintln!("?: {}", error);
 }
 
 impl Environment {
-    pub fn interpret(&mut self) -> Value {
+    pub fn interpret(&mut self) -> Result<Value, HierError> {
         if let Expression::BLOCK(block, _) = self.code.clone() {
-            self.interpret_block(block)
+            self.interpret_top_level(Block::new(block))
         } else {
-            self.visit(self.code.clone())
+            let value = self.visit(self.code.clone())?;
+            self.finish_top_level(value)
         }
     }
 
-    pub fn interpret_block(&mut self, block: Vec<Expression>) -> Value {
+    /// Interprets a block which isn't a part of any function, like a module or a line in REPL.
+    pub fn interpret_top_level(&mut self, block: Block) -> Result<Value, HierError> {
+        let value = self.run_block(block)?;
+        self.finish_top_level(value)
+    }
+
+    /// Return outside of a function ends the code with the returned value.
+    fn finish_top_level(&mut self, value: Value) -> Result<Value, HierError> {
+        match self.control_flow.take() {
+            Some(ControlFlow::RETURN(returned_value)) => Ok(returned_value),
+            Some(ControlFlow::BREAK(_, _)) => self.error("Break must be used inside of a loop (or a loop with its label doesn't exist)."),
+            Some(ControlFlow::CONTINUE(_)) => self.error("Continue must be used inside of a loop (or a loop with its label doesn't exist)."),
+            None => Ok(value)
+        }
+    }
+
+    /// Runs a block passed to a function. Afterwards, the location is the call again, so errors reported by the function, like a condition which isn't a boolean, point at the call on both backends.
+    pub fn interpret_block(&mut self, block: Block) -> Result<Value, HierError> {
+        let location = self.current_interpreting_location.clone();
+        let value = self.run_block(block)?;
+        self.current_interpreting_location = location;
+        Ok(value)
+    }
+
+    fn run_block(&mut self, block: Block) -> Result<Value, HierError> {
+        // Blocks are counted too, so even an empty loop uses steps and time is checked.
+        self.step()?;
+
+        // Debugger inspects expressions, so it always walks the tree.
+        if self.backend == Backend::VM && !self.is_debugging {
+            return self.execute(&block.chunk());
+        }
+
         let mut last_result = Value::NULL;
 
-        for expression in block {
-            last_result = self.visit(expression);
+        for expression in block.expressions.iter() {
+            last_result = self.visit(expression.clone())?;
 
-            if let Value::ERROR(error_message) = last_result.clone() {
-                if error_message == "LoopExit".to_string() {
-                    break;
-                }
+            if self.control_flow.is_some() {
+                break;
             }
         }
 
-        last_result
+        Ok(last_result)
     }
 
-    pub fn visit(&mut self, value: Expression) -> Value {
+    pub fn visit(&mut self, value: Expression) -> Result<Value, HierError> {
         self.current_interpreting_location = value.get_location().clone();
+        self.step()?;
 
         match value {
             Expression::STRING(_, _) => self.visit_string(value),
             Expression::VALUE(_) => self.visit_value(value),
-            Expression::NUMBER(_, _) => self.visit_number(value),
+            Expression::NUMBER(_, _) | Expression::INTEGER(_, _) | Expression::BIG_INTEGER(_, _) | Expression::DECIMAL(_, _) => self.visit_number(value),
             Expression::IDENTIFIER(_, _) => self.visit_identifier(value),
             Expression::LIST(_, _) => self.visit_list(value),
             Expression::BLOCK(_, _) => self.visit_block(value),
@@ -47,24 +83,31 @@ impl Environment {
         }
     }
 
-    pub fn visit_property(&mut self, property: Expression) -> Value {
+    pub fn visit_property(&mut self, property: Expression) -> Result<Value, HierError> {
         self.current_interpreting_location = property.get_location().clone();
 
-        if let Expression::PROPERTY(expression, identifier, _) = property {
-            let argument = self.visit(*(expression.clone()));
-            self.call_function(&("get".to_string()), vec![argument, Value::STRING(identifier)])
+        if let Expression::PROPERTY(expression, identifier, location) = property {
+            let argument = self.visit(*(expression.clone()))?;
+
+            if self.control_flow.is_some() {
+                return Ok(Value::NULL);
+            }
+
+            self.current_interpreting_location = location;
+
+            self.call_function(&("get".to_string()), vec![argument, Value::STRING(identifier.into())])
         } else {
-            Value::NULL
+            Ok(Value::NULL)
         }
     }
 
-    pub fn visit_list(&mut self, list: Expression) -> Value {
+    pub fn visit_list(&mut self, list: Expression) -> Result<Value, HierError> {
         let main = list.clone();
         self.current_interpreting_location = list.get_location().clone();
 
         if let Expression::LIST(list, _) = list {
-            if list.len() == 0 {
-                Value::LIST(vec![])
+            if list.is_empty() {
+                Ok(Value::LIST(List::new()))
             } else {
                 if let Expression::IDENTIFIER(name, _) = &list[0] {
                     if name == "|" {
@@ -77,11 +120,11 @@ impl Environment {
                             if let Expression::IDENTIFIER(argument, _) = expression {
                                 arguments.push(argument);
                             } else {
-                                self.error("Function arguments must be identifiers.");
+                                return self.error("Function arguments must be identifiers.");
                             }
                         }
 
-                        Value::FUNCTION_ARGUMENTS(arguments)
+                        Ok(Value::FUNCTION_ARGUMENTS(arguments))
                     } else {
                         let mut expressions = list.clone();
                         expressions.remove(0);
@@ -89,11 +132,17 @@ impl Environment {
                         let mut values: Vec<Value> = Vec::new();
 
                         for expression in expressions {
-                            values.push(self.visit(expression));
+                            values.push(self.visit(expression)?);
+
+                            if self.control_flow.is_some() {
+                                return Ok(Value::NULL);
+                            }
                         }
 
+                        // Call site is the list, not its last argument.
+                        self.current_interpreting_location = main.get_location().clone();
                         self.current_interpreting_expression = main;
-                        self.call_function(&name, values)
+                        self.call_function(name, values)
                     }
                 } else if let Expression::PROPERTY(expression, identifier, _) = &list[0] {
                     let mut expressions = list.clone();
@@ -103,21 +152,27 @@ impl Environment {
                     let mut values: Vec<Value> = Vec::new();
 
                     for expression in expressions {
-                        values.push(self.visit(expression));
+                        values.push(self.visit(expression)?);
+
+                        if self.control_flow.is_some() {
+                            return Ok(Value::NULL);
+                        }
                     }
 
+                    // Call site is the list, not its last argument.
+                    self.current_interpreting_location = main.get_location().clone();
                     self.current_interpreting_expression = main;
-                    self.call_function(identifier, values)
+                    self.call_method(identifier, values)
                 } else {
-                    if let Value::FUNCTION_ARGUMENTS(arguments) = self.visit(list[0].clone()) {
+                    if let Value::FUNCTION_ARGUMENTS(arguments) = self.visit(list[0].clone())? {
                         if list.len() != 2 {
-                            self.error("Anonymous function's must have 2 arguments: function arguments and a block");
+                            return self.error("Anonymous function's must have 2 arguments: function arguments and a block");
                         }
 
                         if let Expression::BLOCK(block, _) = list[1].clone() {
-                            return Value::FUNCTION(arguments, Box::new(Value::BLOCK(block)));
+                            return Ok(self.make_function(arguments, Block::new(block)));
                         } else {
-                            self.error("Anonymous function's second argument must be a block.");
+                            return self.error("Anonymous function's second argument must be a block.");
                         }
                     }
 
@@ -125,83 +180,89 @@ impl Environment {
                     let mut values: Vec<Value> = Vec::new();
 
                     for expression in expressions {
-                        values.push(self.visit(expression));
+                        values.push(self.visit(expression)?);
+
+                        if self.control_flow.is_some() {
+                            return Ok(Value::NULL);
+                        }
                     }
 
-                    Value::LIST(values)
+                    self.check_size(Value::LIST(values.into()))
                 }
             }
         } else {
-            Value::NULL
+            Ok(Value::NULL)
         }
     }
 
-    pub fn visit_identifier(&mut self, value: Expression) -> Value {
+    pub fn visit_identifier(&mut self, value: Expression) -> Result<Value, HierError> {
         self.current_interpreting_location = value.get_location().clone();
 
         if let Expression::IDENTIFIER(value, _) = value {
             match &value as &str {
-                "true" => Value::BOOL(true),
-                "false" => Value::BOOL(false),
-                "null" => Value::NULL,
+                "true" => Ok(Value::BOOL(true)),
+                "false" => Ok(Value::BOOL(false)),
+                "null" => Ok(Value::NULL),
                 _ => {
                     if let Some(a_type) = Type::get_for_name(&value) {
-                        Value::TYPE(a_type)
+                        Ok(Value::TYPE(a_type))
                     } else {
                         self.get(value)
                     }
                 }
             }
         } else {
-            Value::NULL
+            Ok(Value::NULL)
         }
     }
 
-    pub fn visit_value(&mut self, value: Expression) -> Value {
+    pub fn visit_value(&mut self, value: Expression) -> Result<Value, HierError> {
         if let Expression::VALUE(value) = value {
-            value
+            Ok(value)
         } else {
-            Value::NULL
+            Ok(Value::NULL)
         }
     }
 
-    pub fn visit_key_value(&mut self, value: Expression) -> Value {
+    pub fn visit_key_value(&mut self, value: Expression) -> Result<Value, HierError> {
         self.current_interpreting_location = value.get_location().clone();
 
         if let Expression::KEY_VALUE(identifier, expression, _) = value {
-            Value::KEY_VALUE(identifier, Box::new(self.visit(*expression)))
+            Ok(Value::KEY_VALUE(identifier, Box::new(self.visit(*expression)?)))
         } else {
-            Value::NULL
+            Ok(Value::NULL)
         }
     }
 
-    pub fn visit_string(&mut self, value: Expression) -> Value {
+    pub fn visit_string(&mut self, value: Expression) -> Result<Value, HierError> {
         self.current_interpreting_location = value.get_location().clone();
 
         if let Expression::STRING(value, _) = value {
-            Value::STRING(value.resolve(self))
+            Ok(Value::STRING(value.resolve(self)?.into()))
         } else {
-            Value::NULL
+            Ok(Value::NULL)
         }
     }
 
-    pub fn visit_number(&mut self, value: Expression) -> Value {
+    pub fn visit_number(&mut self, value: Expression) -> Result<Value, HierError> {
         self.current_interpreting_location = value.get_location().clone();
 
-        if let Expression::NUMBER(value, _) = value {
-            Value::NUMBER(value)
-        } else {
-            Value::NULL
+        match value {
+            Expression::NUMBER(value, _) => Ok(Value::NUMBER(value)),
+            Expression::INTEGER(value, _) => Ok(Value::INTEGER(value)),
+            Expression::BIG_INTEGER(value, _) => Ok(Value::BIG_INTEGER(value)),
+            Expression::DECIMAL(value, _) => Ok(Value::DECIMAL(value)),
+            _ => Ok(Value::NULL)
         }
     }
 
-    pub fn visit_block(&mut self, value: Expression) -> Value {
+    pub fn visit_block(&mut self, value: Expression) -> Result<Value, HierError> {
         self.current_interpreting_location = value.get_location().clone();
 
         if let Expression::BLOCK(value, _) = value {
-            Value::BLOCK(value)
+            Ok(Value::BLOCK(Block::new(value)))
         } else {
-            Value::NULL
+            Ok(Value::NULL)
         }
     }
 }
\ No newline at end of file
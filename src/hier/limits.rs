@@ -0,0 +1,117 @@
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+use crate::hier::value::Value;
+
+/// Resources which a single run of the code can use. Code which exceeds them is stopped with an error of kind LIMIT.
+/// By default only the depth of calls is limited, so deep recursion can't use all memory.
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    /// Number of evaluated expressions and blocks (in the VM, executed instructions and blocks), so it differs a bit between backends.
+    pub steps: Option<u64>,
+    /// Time of a run.
+    pub time: Option<Duration>,
+    /// Number of nested calls of user-defined functions.
+    pub depth: Option<usize>,
+    /// Approximate size in bytes of a single value, like a list or a string. It's checked for values made by functions and list literals.
+    /// It doesn't limit the total memory used by the code, which can keep many values smaller than the limit.
+    pub value_size: Option<usize>
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            steps: None,
+            time: None,
+            depth: Some(10_000),
+            value_size: None
+        }
+    }
+}
+
+impl Limits {
+    /// Limits nothing, for trusted code.
+    pub fn unlimited() -> Self {
+        Self {
+            steps: None,
+            time: None,
+            depth: None,
+            value_size: None
+        }
+    }
+}
+
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    STEPS,
+    TIME,
+    DEPTH,
+    VALUE_SIZE
+}
+
+/// Resources used by the current run. It's shared by imported modules and eval, so their code counts to the same limits.
+#[derive(Debug)]
+pub struct Budget {
+    pub limits: Limits,
+    steps: Cell<u64>,
+    depth: Cell<usize>,
+    started: Cell<Instant>
+}
+
+impl Budget {
+    pub fn new(limits: Limits) -> Self {
+        Self {
+            limits,
+            steps: Cell::new(0),
+            depth: Cell::new(0),
+            started: Cell::new(Instant::now())
+        }
+    }
+
+    /// Starts counting a new run.
+    pub fn start(&self) {
+        self.steps.set(0);
+        self.depth.set(0);
+        self.started.set(Instant::now());
+    }
+
+    /// Counts a step. Time is checked only every 1024 steps, because reading the clock is slow compared to a step.
+    pub fn step(&self) -> Result<(), Limit> {
+        let steps = self.steps.get() + 1;
+        self.steps.set(steps);
+
+        if self.limits.steps.is_some_and(|limit| steps > limit) {
+            return Err(Limit::STEPS);
+        }
+
+        if steps.is_multiple_of(1024) && self.limits.time.is_some_and(|limit| self.started.get().elapsed() > limit) {
+            return Err(Limit::TIME);
+        }
+
+        Ok(())
+    }
+
+    /// Counts a call of a function, which has to be ended using leave.
+    pub fn enter(&self) -> Result<(), Limit> {
+        let depth = self.depth.get() + 1;
+
+        if self.limits.depth.is_some_and(|limit| depth > limit) {
+            return Err(Limit::DEPTH);
+        }
+
+        self.depth.set(depth);
+        Ok(())
+    }
+
+    pub fn leave(&self) {
+        self.depth.set(self.depth.get().saturating_sub(1));
+    }
+
+    /// Checks size of a new value. Size is computed only if it's limited. Computing it visits the whole value, so it takes time proportional to the size.
+    pub fn check_size(&self, value: &Value) -> Result<(), Limit> {
+        match self.limits.value_size {
+            Some(limit) if value.approximate_size() > limit => Err(Limit::VALUE_SIZE),
+            _ => Ok(())
+        }
+    }
+}
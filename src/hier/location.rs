@@ -21,4 +21,8 @@ impl Location {
             offset: 0
         }
     }
+
+    pub fn text_representation(&self) -> String {
+        format!("{}:{} in {}", self.line_number, self.offset, self.module)
+    }
 }
\ No newline at end of file
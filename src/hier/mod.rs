@@ -1,16 +1,36 @@
 use crate::hier::location::Location;
 
-pub mod hier;
-pub mod value;
-pub mod types;
-pub mod environment;
-pub mod tokenizer;
-pub mod parser;
-pub mod interpreter;
-pub mod native_functions;
-pub mod token;
-pub mod location;
-pub mod expression;
+#[allow(clippy::module_inception)]
+pub(crate) mod hier;
+pub(crate) mod value;
+pub(crate) mod types;
+pub(crate) mod environment;
+pub(crate) mod tokenizer;
+pub(crate) mod parser;
+pub(crate) mod interpreter;
+pub(crate) mod native_functions;
+pub(crate) mod token;
+pub(crate) mod location;
+pub(crate) mod expression;
+pub(crate) mod block;
+pub(crate) mod frame;
+pub(crate) mod function;
+pub(crate) mod builtin;
+pub(crate) mod backend;
+pub(crate) mod control_flow;
+pub(crate) mod call;
+pub(crate) mod error_value;
+pub(crate) mod hier_error;
+pub(crate) mod conversion;
+pub(crate) mod user_data;
+pub(crate) mod policy;
+pub(crate) mod limits;
+pub(crate) mod cancellation;
+pub(crate) mod compiler;
+pub(crate) mod vm;
+pub(crate) mod number;
+pub(crate) mod pattern;
+pub(crate) mod record;
 mod interpolated_string;
 mod debugger;
 
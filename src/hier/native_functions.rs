@@ -1,157 +1,165 @@
-use std::collections::HashMap;
+use std::cmp::Ordering;
 use std::io;
 use std::io::Write;
+use std::rc::Rc;
+use bigdecimal::RoundingMode;
+use crate::hier::control_flow::ControlFlow;
+use crate::hier::conversion::FromArguments;
 use crate::hier::debugger::debug;
-use crate::hier::environment::Environment;
+use crate::hier::environment::{Environment, module_directory};
+use crate::hier::error_value::ErrorValue;
+use crate::hier::hier_error::HierError;
 use crate::hier::hier::Hier;
+use crate::hier::number;
+use crate::hier::number::Operation;
 use crate::hier::parser::Parser;
-use crate::hier::value::Value;
+use crate::hier::record::RecordType;
+use crate::hier::value::{find_field, Table, Value};
 use crate::hier::tokenizer::Tokenizer;
+use unicode_segmentation::UnicodeSegmentation;
+use crate::hier::types::Type;
 
 impl Environment {
-    pub fn call_addition(&mut self, arguments: Vec<Value>) -> Value {
+    /// Converts arguments of a builtin with a fixed number of arguments, for example (table, key): (Table, String).
+    fn unpack<T: FromArguments>(&self, name: &str, arguments: &[Value]) -> Result<T, HierError> {
+        T::from_arguments(self, name, arguments)
+    }
+
+    pub(crate) fn call_addition(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
+        if arguments.is_empty() {
+            return self.error("Addition requires at least 1 argument.");
+        }
+
         let mut arguments = arguments;
 
-        let mut result_number = 0f64;
+        let mut result_number = Value::INTEGER(0);
         let mut result_string = String::new();
 
         let mut is_number = true;
 
         let first = arguments.remove(0);
 
-        if let Value::NUMBER(number) = first {
-            result_number = number;
+        if first.is_number() {
+            result_number = first;
         } else if let Value::STRING(string) = first {
             is_number = false;
-            result_string = string;
+            result_string = string.to_string();
         } else {
-            self.error(&format!("Argument must be a number or string in addition. Found {}.", first.text_representation()));
+            return self.error(&format!("Argument must be a number or string in addition. Found {}.", first.text_representation()));
         }
 
         for argument in arguments {
-            if let Value::NUMBER(number) = argument {
+            if argument.is_number() {
                 if is_number {
-                    result_number += number;
+                    result_number = self.apply_arithmetic(Operation::ADDITION, &result_number, &argument)?;
                 } else {
-                    self.error(&format!("Argument must be a string, but {} of type {} was found.", argument.text_representation(), argument.get_type().text_representation()))
+                    return self.error(&format!("Argument must be a string, but {} of type {} was found.", argument.text_representation(), argument.get_type().text_representation()))
                 }
             } else if let Value::STRING(string) = argument.clone() {
                 if !is_number {
                     result_string += &string;
                 } else {
-                    self.error(&format!("Argument must be a number, but {} of type {} was found.", argument.text_representation(), argument.get_type().text_representation()))
+                    return self.error(&format!("Argument must be a number, but {} of type {} was found.", argument.text_representation(), argument.get_type().text_representation()))
                 }
             } else {
-                self.error(&format!("Argument must be a number or string in addition. Found {}.", argument.text_representation()));
+                return self.error(&format!("Argument must be a number or string in addition. Found {}.", argument.text_representation()));
             }
         }
 
-        if is_number { Value::NUMBER(result_number) } else { Value::STRING(result_string) }
+        if is_number { Ok(result_number) } else { Ok(Value::STRING(result_string.into())) }
     }
 
-    pub fn call_subtraction(&mut self, arguments: Vec<Value>) -> Value {
-        let mut arguments = arguments;
-        let mut result = if let Value::NUMBER(number) = arguments.remove(0) {
-            number
-        } else {
-            self.error("Argument must be a number in subtraction.")
-        };
-
-        for argument in arguments {
-            if let Value::NUMBER(number) = argument {
-                result -= number;
-            } else {
-                self.error(&format!("Argument must be a number in subtraction. Found {}.", argument.text_representation()));
-            }
-        }
+    pub(crate) fn call_subtraction(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
+        self.fold_arithmetic(Operation::SUBTRACTION, arguments)
+    }
 
-        Value::NUMBER(result)
+    pub(crate) fn call_multiplication(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
+        self.fold_arithmetic(Operation::MULTIPLICATION, arguments)
     }
 
-    pub fn call_multiplication(&mut self, arguments: Vec<Value>) -> Value {
-        let mut arguments = arguments;
-        let mut result = if let Value::NUMBER(number) = arguments.remove(0) {
-            number
-        } else {
-            self.error("Argument must be a number in multiplication.")
-        };
+    /// Division of integers and floats produces a float. Integer division is done by div.
+    pub(crate) fn call_division(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
+        self.fold_arithmetic(Operation::DIVISION, arguments)
+    }
 
-        for argument in arguments {
-            if let Value::NUMBER(number) = argument {
-                result *= number;
-            } else {
-                self.error(&format!("Argument must be a number in multiplication. Found {}.", argument.text_representation()));
-            }
+    /// Division which drops the fraction of the result. Its result has the same kind as the numbers.
+    pub(crate) fn call_integer_division(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
+        if arguments.len() != 2 {
+            return self.error("Div requires only 2 operands");
         }
 
-        Value::NUMBER(result)
+        self.apply_arithmetic(Operation::INTEGER_DIVISION, &arguments[0], &arguments[1])
     }
 
-    pub fn call_division(&mut self, arguments: Vec<Value>) -> Value {
+    /// Applies an operation to the first argument and each next argument.
+    fn fold_arithmetic(&self, operation: Operation, arguments: Vec<Value>) -> Result<Value, HierError> {
+        if arguments.is_empty() {
+            return self.error(&format!("At least 1 argument is required in {}.", operation.name()));
+        }
+
         let mut arguments = arguments;
-        let mut result = if let Value::NUMBER(number) = arguments.remove(0) {
-            number
-        } else {
-            self.error("Argument must be a number in division.")
-        };
+        let mut result = arguments.remove(0);
 
-        for argument in arguments {
-            if let Value::NUMBER(number) = argument {
-                if number == 0.0 {
-                    self.error("Dividing by 0 is forbidden.");
-                }
+        if !result.is_number() {
+            return self.error(&format!("Argument must be a number in {}.", operation.name()))
+        }
 
-                result /= number;
-            } else {
-                self.error(&format!("Argument must be a number in division. Found {}.", argument.text_representation()));
-            }
+        for argument in arguments {
+            result = self.apply_arithmetic(operation, &result, &argument)?;
         }
 
-        Value::NUMBER(result)
+        Ok(result)
     }
 
-    pub fn call_null_coalescing(&self, arguments: Vec<Value>) -> Value {
+    fn apply_arithmetic(&self, operation: Operation, first: &Value, second: &Value) -> Result<Value, HierError> {
+        match number::apply(operation, first, second) {
+            Ok(value) => Ok(value),
+            Err(message) => self.error(&message)
+        }
+    }
+
+    pub(crate) fn call_null_coalescing(&self, arguments: Vec<Value>) -> Result<Value, HierError> {
         for argument in arguments {
             if let Value::NULL = argument {
                 continue
             } else {
-                return argument
+                return Ok(argument)
             }
         }
 
-        Value::NULL
+        Ok(Value::NULL)
     }
 
-    pub fn call_modulo(&self, arguments: Vec<Value>) -> Value {
+    pub(crate) fn call_modulo(&self, arguments: Vec<Value>) -> Result<Value, HierError> {
         if arguments.len() != 2 {
-            self.error("Modulo requires only 2 operands");
+            return self.error("Modulo requires only 2 operands");
         }
 
-        if let Value::NUMBER(number1) = arguments[0].clone() {
-            if let Value::NUMBER(number2) = arguments[1].clone() {
-                Value::NUMBER(((number1 as i64) % (number2 as i64)) as f64)
-            } else {
-                self.error("Modulo requires 2 number arguments.");
-            }
-        } else {
-            self.error("Modulo requires 2 number arguments.");
+        if !arguments[0].is_number() || !arguments[1].is_number() {
+            return self.error("Modulo requires 2 number arguments.");
         }
+
+        self.apply_arithmetic(Operation::MODULO, &arguments[0], &arguments[1])
     }
 
-    pub fn call_is(&self, arguments: Vec<Value>) -> Value {
+    pub(crate) fn call_is(&self, arguments: Vec<Value>) -> Result<Value, HierError> {
         if arguments.len() != 2 {
-            self.error("Is requires only 2 operands");
+            return self.error("Is requires only 2 operands");
         }
 
-        if let Value::TYPE(a_type) = arguments[1].clone() {
-            Value::BOOL(arguments[0].get_type() == a_type)
-        } else {
-            self.error("Is operation requires second argument to be a value type.")
+        match &arguments[1] {
+            Value::TYPE(a_type) => Ok(Value::BOOL(arguments[0].is_of_type(a_type))),
+            // Types of host objects don't have identifiers, so they are compared by name.
+            Value::STRING(name) => match Type::get_for_name(name) {
+                Some(a_type) => Ok(Value::BOOL(arguments[0].is_of_type(&a_type))),
+                None => Ok(Value::BOOL(arguments[0].get_type().text_representation() == **name))
+            },
+            _ => self.error("Is operation requires second argument to be a value type or a name of a type.")
         }
     }
 
-    pub fn call_comparison(&mut self, operation: &String, arguments: Vec<Value>) -> Value {
-        match &operation as &str {
+    pub(crate) fn call_comparison(&mut self, operation: &str, arguments: Vec<Value>) -> Result<Value, HierError> {
+        match operation as &str {
             "==" => {
                 for (i, argument) in arguments.iter().enumerate() {
                     if i == 0 {
@@ -159,11 +167,11 @@ impl Environment {
                     }
 
                     if argument.clone() != arguments[i - 1] {
-                        return Value::BOOL(false)
+                        return Ok(Value::BOOL(false))
                     }
                 }
 
-                return Value::BOOL(true)
+                Ok(Value::BOOL(true))
             },
             "!=" => {
                 for (i, argument) in arguments.iter().enumerate() {
@@ -172,11 +180,11 @@ impl Environment {
                     }
 
                     if argument.clone() == arguments[i - 1] {
-                        return Value::BOOL(false)
+                        return Ok(Value::BOOL(false))
                     }
                 }
 
-                return Value::BOOL(true)
+                Ok(Value::BOOL(true))
             },
             "<" => {
                 for (i, argument) in arguments.iter().enumerate() {
@@ -184,20 +192,18 @@ impl Environment {
                         continue
                     }
 
-                    if let Value::NUMBER(number2) = argument {
-                        if let Value::NUMBER(number1) = arguments[i - 1] {
-                            if number1 >= number2.clone() {
-                                return Value::BOOL(false)
-                            }
-                        } else {
-                            self.error("< comparison operands must be numbers.")
-                        }
-                    } else {
-                        self.error("< comparison operands must be numbers.")
+                    let (number1, number2) = (&arguments[i - 1], argument);
+
+                    if !number1.is_number() || !number2.is_number() {
+                        return self.error("< comparison operands must be numbers.")
+                    }
+
+                    if number::compare(number1, number2) != Some(Ordering::Less) {
+                        return Ok(Value::BOOL(false))
                     }
                 }
 
-                return Value::BOOL(true)
+                Ok(Value::BOOL(true))
             },
             ">" => {
                 for (i, argument) in arguments.iter().enumerate() {
@@ -205,20 +211,18 @@ impl Environment {
                         continue
                     }
 
-                    if let Value::NUMBER(number2) = argument {
-                        if let Value::NUMBER(number1) = arguments[i - 1] {
-                            if number1 <= number2.clone() {
-                                return Value::BOOL(false)
-                            }
-                        } else {
-                            self.error("> comparison operands must be numbers.")
-                        }
-                    } else {
-                        self.error("> comparison operands must be numbers.")
+                    let (number1, number2) = (&arguments[i - 1], argument);
+
+                    if !number1.is_number() || !number2.is_number() {
+                        return self.error("> comparison operands must be numbers.")
+                    }
+
+                    if number::compare(number1, number2) != Some(Ordering::Greater) {
+                        return Ok(Value::BOOL(false))
                     }
                 }
 
-                return Value::BOOL(true)
+                Ok(Value::BOOL(true))
             },
             "<=" => {
                 for (i, argument) in arguments.iter().enumerate() {
@@ -226,20 +230,18 @@ impl Environment {
                         continue
                     }
 
-                    if let Value::NUMBER(number2) = argument {
-                        if let Value::NUMBER(number1) = arguments[i - 1] {
-                            if number1 > number2.clone() {
-                                return Value::BOOL(false)
-                            }
-                        } else {
-                            self.error("<= comparison operands must be numbers.")
-                        }
-                    } else {
-                        self.error("<= comparison operands must be numbers.")
+                    let (number1, number2) = (&arguments[i - 1], argument);
+
+                    if !number1.is_number() || !number2.is_number() {
+                        return self.error("<= comparison operands must be numbers.")
+                    }
+
+                    if !matches!(number::compare(number1, number2), Some(Ordering::Less | Ordering::Equal)) {
+                        return Ok(Value::BOOL(false))
                     }
                 }
 
-                return Value::BOOL(true)
+                Ok(Value::BOOL(true))
             },
             ">=" => {
                 for (i, argument) in arguments.iter().enumerate() {
@@ -247,108 +249,116 @@ impl Environment {
                         continue
                     }
 
-                    if let Value::NUMBER(number2) = argument {
-                        if let Value::NUMBER(number1) = arguments[i - 1] {
-                            if number1 < number2.clone() {
-                                return Value::BOOL(false)
-                            }
-                        } else {
-                            self.error(">= comparison operands must be numbers.")
-                        }
-                    } else {
-                        self.error(">= comparison operands must be numbers.")
+                    let (number1, number2) = (&arguments[i - 1], argument);
+
+                    if !number1.is_number() || !number2.is_number() {
+                        return self.error(">= comparison operands must be numbers.")
+                    }
+
+                    if !matches!(number::compare(number1, number2), Some(Ordering::Greater | Ordering::Equal)) {
+                        return Ok(Value::BOOL(false))
                     }
                 }
 
-                return Value::BOOL(true)
+                Ok(Value::BOOL(true))
             }
-            _ => Value::NULL // We never reach this place, because call_function checks whether the operation is a valid one for this function.
+            _ => Ok(Value::NULL) // We never reach this place, because call_function checks whether the operation is a valid one for this function.
         }
     }
 
-    pub fn call_logical(&mut self, operation: &String, arguments: Vec<Value>) -> Value {
-        if arguments.len() == 0 {
-            return Value::BOOL(true);
+    pub(crate) fn call_logical(&mut self, operation: &str, arguments: Vec<Value>) -> Result<Value, HierError> {
+        if arguments.is_empty() {
+            return Ok(Value::BOOL(true));
         }
 
         for argument in arguments {
             if let Value::BOOL(value) = argument {
                 if operation == "&&" {
                     if !value {
-                        return Value::BOOL(false);
+                        return Ok(Value::BOOL(false));
                     }
                 } else { // ||
                     if value {
-                        return Value::BOOL(true);
+                        return Ok(Value::BOOL(true));
                     }
                 };
             } else {
-                self.error("Operands of logical operations must be booleans or boolean expressions.")
+                return self.error("Operands of logical operations must be booleans or boolean expressions.")
             }
         }
 
         if operation == "&&" {
-            Value::BOOL(true)
+            Ok(Value::BOOL(true))
         } else { // ||
-            Value::BOOL(false)
+            Ok(Value::BOOL(false))
         }
     }
 
-    pub fn call_if(&mut self, arguments: Vec<Value>) -> Value {
+    pub(crate) fn call_if(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
         if arguments.len() != 2 && arguments.len() != 3 {
-            self.error("If must have only 2 or 3 arguments: condition and block (optionally else block).");
+            return self.error("If must have only 2 or 3 arguments: condition and block (optionally else block).");
         }
 
         let condition = if let Value::BOOL(condition) = arguments[0] {
             condition
         } else {
-            self.error("If's condition must evaluate to a boolean.");
+            return self.error("If's condition must evaluate to a boolean.");
         };
 
         self.begin_scope();
 
-        if condition {
-            return if let Value::BLOCK(block) = arguments[1].clone() {
-                let value = self.interpret_block(block);
-                value
+        let value = if condition {
+            if let Value::BLOCK(block) = arguments[1].clone() {
+                self.interpret_block(block)?
             } else {
                 arguments[1].clone()
             }
         } else if arguments.len() == 3 {
-            return if let Value::BLOCK(block) = arguments[2].clone() {
-                let value = self.interpret_block(block);
-                value
+            if let Value::BLOCK(block) = arguments[2].clone() {
+                self.interpret_block(block)?
             } else {
                 arguments[2].clone()
             }
-        }
+        } else {
+            Value::NULL
+        };
 
-        self.end_scope();
+        self.end_scope()?;
 
-        Value::NULL
+        Ok(value)
     }
 
-    pub fn call_while(&mut self, arguments: Vec<Value>) -> Value {
+    pub(crate) fn call_while(&mut self, mut arguments: Vec<Value>) -> Result<Value, HierError> {
+        let label = self.take_label(&mut arguments)?;
+
         if arguments.len() != 2 {
-            self.error("While must have 2 arguments: a condition block and an execution block.");
+            return self.error("While must have 2 arguments: a condition block and an execution block.");
         }
 
         self.begin_scope();
 
+        let mut result = Value::NULL;
+
         if let Value::BLOCK(_) = arguments[0] { } else {
-            self.error("While's first argument must be a block.");
+            return self.error("While's first argument must be a block.");
         }
 
         if let Value::BLOCK(block) = arguments[1].clone() {
             loop {
                 let condition = if let Value::BLOCK(condition_block) = arguments[0].clone() {
-                    if let Value::BOOL(condition) = self.interpret_block(condition_block) {
+                    let condition = self.interpret_block(condition_block)?;
+
+                    if self.should_exit_loop(&label, &mut result) {
+                        break;
+                    }
+
+                    if let Value::BOOL(condition) = condition {
                         condition
                     } else {
-                        self.error("While's condition must return a boolean (boolean must be the last expression's result).");
+                        return self.error("While's condition must return a boolean (boolean must be the last expression's result).");
                     }
                 } else {
-                    self.error("While's condition must be a condition block returning a boolean (boolean must be the last expression's result).");
+                    return self.error("While's condition must be a condition block returning a boolean (boolean must be the last expression's result).");
                 };
 
                 if !condition {
@@ -356,189 +366,228 @@ impl Environment {
                 }
 
                 self.begin_scope();
-                if let Value::ERROR(error_message) = self.interpret_block(block.clone()) {
-                    if error_message == "LoopExit".to_string() {
-                        self.end_scope();
-                        self.end_scope();
-                        break;
-                    }
+                self.interpret_block(block.clone())?;
+                self.end_scope()?;
+
+                if self.should_exit_loop(&label, &mut result) {
+                    break;
                 }
-                self.end_scope();
             }
         }
 
-        self.end_scope();
+        self.end_scope()?;
 
-        Value::NULL
+        Ok(result)
     }
 
-    pub fn call_try(&mut self, arguments: Vec<Value>) -> Value {
+    pub(crate) fn call_try(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
         if arguments.len() != 2 {
-            self.error("Try must have 2 arguments: a value and execution block.");
+            return self.error("Try must have 2 arguments: a value and execution block.");
         }
 
         self.begin_scope();
 
-        let result = if let Value::ERROR(error_message) = arguments[0].clone() {
+        let result = if let Value::ERROR(_) = arguments[0] {
             if let Value::BLOCK(block) = arguments[1].clone() {
-                self.declare("error".to_string(), Value::STRING(error_message));
-                let value = self.interpret_block(block.clone());
-                self.end_scope();
-                value
+                self.declare("error".to_string(), arguments[0].clone())?;
+                self.interpret_block(block.clone())?
             } else {
-                self.error("Try's second argument must be a block.");
+                return self.error("Try's second argument must be a block.");
             }
         } else {
             arguments[0].clone()
         };
 
-        self.end_scope();
+        self.end_scope()?;
+
+        Ok(result)
+    }
+
+    /// Runs the block of the first case whose pattern matches the value and whose guard (if any) is true. Variables bound by the pattern are declared in a new scope.
+    /// Returns null if no case matches. Patterns are made by the parser, so every case has a pattern, a guard (or null) and a block.
+    pub(crate) fn call_match(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
+        if arguments.is_empty() || !(arguments.len() - 1).is_multiple_of(3) {
+            return self.error("Match must have a value and cases: patterns with blocks.");
+        }
+
+        for case in arguments[1..].chunks(3) {
+            let (Value::PATTERN(pattern), Value::BLOCK(block)) = (&case[0], &case[2]) else {
+                return self.error("Case of match must have a pattern and a block.");
+            };
+
+            let pattern = self.resolve_pattern(&pattern.0)?;
+            let mut bindings = vec![];
+
+            if !pattern.matches(&arguments[0], &mut bindings) {
+                continue;
+            }
+
+            self.begin_scope();
+
+            for (name, value) in bindings {
+                self.declare(name, value)?;
+            }
 
-        result
+            if let Value::BLOCK(guard) = &case[1] {
+                match self.interpret_block(guard.clone())? {
+                    Value::BOOL(true) => {},
+                    Value::BOOL(false) => {
+                        self.end_scope()?;
+                        continue;
+                    },
+                    _ => return self.error("Guard of a match case must evaluate to a boolean.")
+                }
+            }
+
+            let result = self.interpret_block(block.clone())?;
+            self.end_scope()?;
+
+            return Ok(result);
+        }
+
+        Ok(Value::NULL)
     }
 
-    pub fn call_for(&mut self, arguments: Vec<Value>) -> Value {
+    pub(crate) fn call_for(&mut self, mut arguments: Vec<Value>) -> Result<Value, HierError> {
+        let label = self.take_label(&mut arguments)?;
+
         if arguments.len() != 2 {
-            self.error("For must have 2 arguments: a list or a string and execution block.");
+            return self.error("For must have 2 arguments: a list or a string and execution block.");
         }
 
         self.begin_scope();
 
+        let mut result = Value::NULL;
+
         if let Value::LIST(list) = arguments[0].clone() {
             if let Value::BLOCK(block) = arguments[1].clone() {
                 for element in list {
                     self.begin_scope();
-                    self.declare("element".to_string(), element);
-                    if let Value::ERROR(error_message) = self.interpret_block(block.clone()) {
-                        if error_message == "LoopExit".to_string() {
-                            self.end_scope();
-                            self.end_scope();
-                            break;
-                        }
+                    self.declare("element".to_string(), element)?;
+                    self.interpret_block(block.clone())?;
+                    self.end_scope()?;
+
+                    if self.should_exit_loop(&label, &mut result) {
+                        break;
                     }
-                    self.end_scope();
                 }
             } else {
-                self.error("For's second argument must be a block.");
+                return self.error("For's second argument must be a block.");
             }
         } else if let Value::STRING(string) = arguments[0].clone() {
             if let Value::BLOCK(block) = arguments[1].clone() {
                 for element in string.chars() {
                     self.begin_scope();
-                    self.declare("element".to_string(), Value::STRING(element.to_string()));
-                    if let Value::ERROR(error_message) = self.interpret_block(block.clone()) {
-                        if error_message == "LoopExit".to_string() {
-                            self.end_scope();
-                            self.end_scope();
-                            break;
-                        }
+                    self.declare("element".to_string(), Value::STRING(element.to_string().into()))?;
+                    self.interpret_block(block.clone())?;
+                    self.end_scope()?;
+
+                    if self.should_exit_loop(&label, &mut result) {
+                        break;
                     }
-                    self.end_scope();
                 }
             } else {
-                self.error("For's second argument must be a block.");
+                return self.error("For's second argument must be a block.");
             }
         } else if let Value::TABLE(table) = arguments[0].clone() {
             if let Value::BLOCK(block) = arguments[1].clone() {
                 for (key, value) in table.iter() {
                     self.begin_scope();
-                    self.declare("element".to_string(), Value::KEY_VALUE(key.to_string(), Box::new(value.clone())));
-                    if let Value::ERROR(error_message) = self.interpret_block(block.clone()) {
-                        if error_message == "LoopExit".to_string() {
-                            self.end_scope();
-                            self.end_scope();
-                            break;
-                        }
+                    self.declare("element".to_string(), Value::KEY_VALUE(key.to_string(), Box::new(value.clone())))?;
+                    self.interpret_block(block.clone())?;
+                    self.end_scope()?;
+
+                    if self.should_exit_loop(&label, &mut result) {
+                        break;
                     }
-                    self.end_scope();
                 }
             } else {
-                self.error("For's second argument must be a block.");
+                return self.error("For's second argument must be a block.");
             }
         } else {
-            self.error("For's first argument must be a list.");
+            return self.error("For's first argument must be a list.");
         };
 
-        self.end_scope();
+        self.end_scope()?;
 
-        Value::NULL
+        Ok(result)
     }
 
-    pub fn call_repeat(&mut self, arguments: Vec<Value>) -> Value {
+    pub(crate) fn call_repeat(&mut self, mut arguments: Vec<Value>) -> Result<Value, HierError> {
+        let label = self.take_label(&mut arguments)?;
+
         if arguments.len() != 2 && arguments.len() != 1 {
-            self.error("Repeat must have only 2 arguments: a number (optional) and execution block.");
+            return self.error("Repeat must have only 2 arguments: a number (optional) and execution block.");
         }
 
-         self.begin_scope();
-
-        if arguments.len() == 2 {
-            let repetitions = if let Value::NUMBER(number) = arguments[0].clone() {
+        let repetitions = if arguments.len() == 2 {
+            if let Some(number) = arguments[0].as_number() {
                 if number < 1f64 {
-                    self.error("Repeat's first argument must be a number greater than 0.");
+                    return self.error("Repeat's first argument must be a number greater than 0.");
                 }
-                number as i64
+                Some(number as i64)
             } else {
-                self.error("Repeat's first argument must be a number.");
-            };
-
-            if let Value::BLOCK(block) = arguments[1].clone() {
-                for _ in 0..repetitions {
-                    self.begin_scope();
-                    if let Value::ERROR(error_message) = self.interpret_block(block.clone()) {
-                        if error_message == "LoopExit".to_string() {
-                            self.end_scope();
-                            self.end_scope();
-                            break;
-                        }
-                    }
-                    self.end_scope();
-                }
+                return self.error("Repeat's first argument must be a number.");
             }
         } else {
-            loop {
-                if let Value::BLOCK(block) = arguments[0].clone() {
-                    self.begin_scope();
-                    if let Value::ERROR(error_message) = self.interpret_block(block.clone()) {
-                        if error_message == "LoopExit".to_string() {
-                            self.end_scope();
-                            self.end_scope();
-                            break;
-                        }
-                    }
-                    self.end_scope();
-                }
+            None
+        };
+
+        // Without a block, an infinite loop would never reach checks of limits.
+        let Some(Value::BLOCK(block)) = arguments.pop() else {
+            return self.error("Repeat's argument must be a block.");
+        };
+
+        self.begin_scope();
+
+        let mut result = Value::NULL;
+        let mut iteration = 0;
+
+        while repetitions.is_none_or(|repetitions| iteration < repetitions) {
+            iteration += 1;
+
+            self.begin_scope();
+            self.interpret_block(block.clone())?;
+            self.end_scope()?;
+
+            if self.should_exit_loop(&label, &mut result) {
+                break;
             }
         }
 
-        self.end_scope();
+        self.end_scope()?;
 
-        Value::NULL
+        Ok(result)
     }
 
-    pub fn call_run(&mut self, arguments: Vec<Value>) -> Value {
+    pub(crate) fn call_run(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
         let mut last_result = Value::NULL;
 
         for argument in arguments {
             if let Value::BLOCK(block) = argument {
-                last_result = self.interpret_block(block);
+                last_result = self.interpret_block(block)?;
+
+                if self.control_flow.is_some() {
+                    break;
+                }
             } else {
                 last_result = argument;
             }
         }
 
-        last_result
+        Ok(last_result)
     }
 
-    pub fn call_map(&mut self, arguments: Vec<Value>) -> Value {
+    pub(crate) fn call_map(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
         if arguments.len() != 2 {
-            self.error("Map function requires 2 arguments: a object and a block.");
+            return self.error("Map function requires 2 arguments: a object and a block.");
         }
 
         let object = &arguments[0];
         let block = if let Value::BLOCK(block) = &arguments[1] {
             block
         } else {
-            self.error("Map functions 2nd argument must be a block.");
+            return self.error("Map functions 2nd argument must be a block.");
         };
 
         match object {
@@ -547,456 +596,688 @@ impl Environment {
 
                 for element in list {
                     self.begin_scope();
-                    self.declare("element".to_string(), element.clone());
-                    new_list.push(self.interpret_block(block.clone()));
-                    self.end_scope();
+                    self.declare("element".to_string(), element.clone())?;
+                    new_list.push(self.interpret_block(block.clone())?);
+                    self.end_scope()?;
+
+                    if self.control_flow.is_some() {
+                        break;
+                    }
                 }
 
-                Value::LIST(new_list)
+                Ok(Value::LIST(new_list.into()))
             },
             _ => {
                 self.begin_scope();
-                self.declare("element".to_string(), object.clone());
-                let result = self.interpret_block(block.clone());
-                self.end_scope();
-                result
+                self.declare("element".to_string(), object.clone())?;
+                let result = self.interpret_block(block.clone())?;
+                self.end_scope()?;
+                Ok(result)
             }
         }
     }
 
-    pub fn call_print(&mut self, arguments: Vec<Value>) -> Value {
+    pub(crate) fn call_print(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
         for argument in arguments {
             print!("{}", argument.text_representation());
         }
 
         std::io::stdout().flush().expect("Failed to flush stdout.");
 
-        Value::NULL
+        Ok(Value::NULL)
     }
 
-    pub fn call_println(&mut self, arguments: Vec<Value>) -> Value {
+    pub(crate) fn call_println(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
         for argument in arguments {
             print!("{}", argument.text_representation());
         }
 
-        print!("\n");
+        println!();
 
-        Value::NULL
+        Ok(Value::NULL)
     }
 
-    pub fn call_list(&mut self, arguments: Vec<Value>) -> Value {
-        Value::LIST(arguments)
+    pub(crate) fn call_list(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
+        Ok(Value::LIST(arguments.into()))
     }
 
-    pub fn call_read(&mut self, arguments: Vec<Value>) -> Value {
-        if arguments.len() == 0 {
-            let mut line = String::new();
-            if let Err(error_message) = io::stdin().read_line(&mut line) {
-                self.error(&format!("Failed to read line: {}.", error_message));
-            };
-            if 1 < line.len() {
-                line.remove(line.len() - 1);
-            } else {
-                line = "".to_string();
-            }
+    pub(crate) fn call_read(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
+        let () = self.unpack("read", &arguments)?;
 
-            Value::STRING(line)
-        } else {
-            self.error("Read operation requires 0 arguments.");
+        let mut line = String::new();
+        if let Err(error_message) = io::stdin().read_line(&mut line) {
+            return self.error(&format!("Failed to read line: {}.", error_message));
+        };
+        if line.ends_with('\n') {
+            line.pop();
+
+            if line.ends_with('\r') {
+                line.pop();
+            }
         }
+
+        Ok(Value::STRING(line.into()))
     }
 
-    pub fn call_negate(&mut self, arguments: Vec<Value>) -> Value {
-        if arguments.len() != 1 {
-            self.error("Negation requires 1 boolean argument.");
+    pub(crate) fn call_negate(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
+        let (boolean,): (bool,) = self.unpack("!", &arguments)?;
+        Ok(Value::BOOL(!boolean))
+    }
+
+    pub(crate) fn call_import(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
+        let (mut path,): (String,) = self.unpack("import", &arguments)?;
+
+        let origin_path = module_directory(&self.path);
+
+        if path.starts_with("./") {
+            path.remove(0);
+            path.remove(0);
+            path = origin_path.clone() + &path;
         }
 
-        if let Value::BOOL(boolean) = arguments[0] {
-            Value::BOOL(!boolean)
-        } else {
-            self.error("Negation requires 1 boolean argument.");
+        if !path.starts_with("/") {
+            path = origin_path.clone() + &path;
         }
-    }
 
-    pub fn call_import(&mut self, arguments: Vec<Value>) -> Value {
-        if arguments.len() != 1 {
-            self.error("Import requires 1 string argument.");
+        if !path.ends_with(".hier") {
+            path += ".hier";
         }
 
-        if let Value::STRING(path) = arguments[0].clone() {
-            let mut origin_path = self.path.clone();
+        if !self.policy.can_import(&path, &self.root) {
+            return Ok(self.make_error("PermissionError", format!("Importing {} is not allowed, because it's outside of {}.", path, self.root)));
+        }
 
-            if origin_path.starts_with("./") {
-                origin_path.remove(0);
-                origin_path.remove(0);
-                origin_path = std::env::current_dir().unwrap().to_str().unwrap().to_string() + "/" + &origin_path;
-            }
+        let contents = match (self.module_reader)(path.clone()) {
+            Ok(contents) => contents,
+            Err(message) => return self.error(&format!("Failed to import file {}: {}", path, message))
+        };
 
-            if !origin_path.ends_with("/") {
-                let mut origin_path_split = origin_path.split("/").collect::<Vec<&str>>();
-                origin_path_split.remove(origin_path_split.len() - 1);
-                origin_path = origin_path_split.join("/");
-                origin_path += "/"
-            }
+        let mut tokenizer = Tokenizer::new_with_name(contents, path.clone());
+
+        if tokenizer.tokenize_module() {
+            return self.error(&format!("Failed to import file {}: {}", path, syntax_error(&tokenizer.errors)));
+        }
 
-            let mut path = path;
+        let mut parser = Parser::new(tokenizer.tokens);
 
-            if path.starts_with("./") {
-                path.remove(0);
-                path.remove(0);
-                path = origin_path.clone() + &path;
-            }
+        if parser.parse() {
+            return self.error(&format!("Failed to import file {}: {}", path, syntax_error(&parser.errors)));
+        }
 
-            if !path.starts_with("/") {
-                path = origin_path.clone() + &path;
-            }
+        let mut environment = Environment::new(false, path, self.module_reader.clone(), self.exit_handler.clone(), self.is_debugging, self.breakpoints.clone());
+        environment.backend = self.backend;
+        environment.policy = self.policy.clone();
+        environment.root = self.root.clone();
+        environment.budget = self.budget.clone();
+        environment.cancellation = self.cancellation.clone();
 
-            if !path.ends_with(".hier") {
-                path += ".hier";
-            }
+        environment.code = parser.code;
+        environment.interpret()?;
 
-            let contents = (self.module_reader)(path.clone());
+        Ok(Value::ENVIRONMENT(Box::new(environment)))
+    }
 
-            let mut tokenizer = Tokenizer::new_with_name(contents, path.clone());
+    /// Strings which aren't numbers return errors of kind ValueError, so they can be handled using try.
+    pub(crate) fn call_number(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
+        let (string,): (String,) = self.unpack("number", &arguments)?;
 
-            if tokenizer.tokenize_module() {
-                eprintln!("Failed to import file {}.", path);
-                (self.exit_handler)()
-            }
+        if let Some(number) = number::parse(&string) {
+            return Ok(number);
+        }
 
-            let mut parser = Parser::new(tokenizer.tokens, self.module_reader, self.exit_handler);
+        let float = if !string.contains('.') {
+            string.clone() + ".0"
+        } else {
+            string.clone()
+        };
 
-            if parser.parse() {
-                println!("Failed.");
-                (self.exit_handler)();
+        match float.parse::<f64>() {
+            Ok(number) => Ok(Value::NUMBER(number)),
+            Err(error) => Ok(self.make_error("ValueError", format!("Failed to convert {} to a number: {}.", string, error)))
+        }
+    }
+
+    pub(crate) fn call_table(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
+        let mut table = Table::new();
+
+        for argument in arguments {
+            if let Value::KEY_VALUE(key, value) = argument {
+                table.insert(key, *value);
+            } else {
+                return self.error(&format!("Table operation's all arguments must be key-values, but {} was found.", argument.text_representation()));
             }
+        }
 
-            let mut environment = Environment::new(false, path, self.module_reader, self.exit_handler, self.is_debugging, self.breakpoints.clone());
+        Ok(Value::TABLE(table))
+    }
 
-            environment.code = parser.code;
-            environment.interpret();
+    /// Makes an object: a table whose prototype is the given table. If the prototype has an init method, it's called with the object and other arguments and its result is returned.
+    /// Otherwise, other arguments must be key-values, which become fields of the object.
+    pub(crate) fn call_new(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
+        let mut arguments = arguments;
 
-            Value::ENVIRONMENT(Box::new(environment))
-        } else {
-            self.error("Import requires 1 string argument.");
+        let prototype = match arguments.first() {
+            Some(Value::TABLE(prototype)) => prototype.clone(),
+            _ => return self.error("New operation requires a prototype (table) as the first argument.")
+        };
+
+        let mut object = Table::new();
+        object.insert("prototype".to_string(), arguments.remove(0));
+
+        if let Some(init) = find_field(&prototype, "init").cloned() {
+            arguments.insert(0, Value::TABLE(object));
+            return self.call_value("init", init, arguments);
+        }
+
+        for argument in arguments {
+            if let Value::KEY_VALUE(key, value) = argument {
+                object.insert(key, *value);
+            } else {
+                return self.error(&format!("New operation requires key-values after the prototype (unless it has an init method), but {} was found.", argument.text_representation()));
+            }
         }
+
+        Ok(Value::TABLE(object))
     }
 
-    pub fn call_number(&mut self, arguments: Vec<Value>) -> Value {
-        if arguments.len() != 1 {
-            self.error("Number conversion requires 1 argument.");
+    /// Returns a copy of a table with a field set or a copy of a list with an element replaced (negative index counts from the end).
+    pub(crate) fn call_set(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
+        if arguments.len() != 3 {
+            return self.error("Set operation requires 3 arguments: a table, a record or a list, key (string for tables and records, number for lists) and value.");
         }
 
-        if let Value::STRING(string) = arguments[0].clone() {
-            let string = if !string.contains('.') {
-                string + ".0"
-            } else {
-                string
-            };
+        let mut arguments = arguments;
+        let value = arguments.remove(2);
 
-            let number = string.parse::<f64>();
+        match (arguments.remove(0), &arguments[0]) {
+            (Value::TABLE(mut table), Value::STRING(key)) => {
+                table.insert(key.to_string(), value);
+                Ok(Value::TABLE(table))
+            },
+            (Value::RECORD(record), Value::STRING(key)) => match record.with_property(key, value) {
+                Ok(record) => Ok(Value::RECORD(Rc::new(record))),
+                Err(message) => self.error(&message)
+            },
+            (Value::LIST(mut list), index) if index.is_number() => {
+                let index = self.resolve_index(index, list.len(), false)?;
+                list[index] = value;
+                Ok(Value::LIST(list))
+            },
+            (object, key) => self.error(&format!("Set operation requires a table or a record with a string key or a list with a number index, but {} of type {} and {} were found.", object.text_representation(), object.get_type().text_representation(), key.text_representation()))
+        }
+    }
 
-            match number {
-                Ok(number) => Value::NUMBER(number),
-                Err(err) => {
-                    println!("Warning: Failed to convert number {} due to an error: {}. Returning 0.", string, err);
-                    Value::NUMBER(0f64)
-                }
+    /// Returns a copy of a table without a field. Table is returned unchanged if it doesn't have the field.
+    pub(crate) fn call_delete(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
+        let (mut table, key): (Table, String) = self.unpack("delete", &arguments)?;
+        table.remove(&key);
+        Ok(Value::TABLE(table))
+    }
+
+    pub(crate) fn call_has(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
+        let (table, key): (Table, String) = self.unpack("has", &arguments)?;
+        Ok(Value::BOOL(table.contains_key(&key)))
+    }
+
+    /// Returns keys of a table in alphabetical order, because fields aren't ordered.
+    pub(crate) fn call_keys(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
+        let (table,): (Table,) = self.unpack("keys", &arguments)?;
+        let mut keys: Vec<&String> = table.keys().collect();
+        keys.sort();
+        Ok(Value::LIST(keys.into_iter().map(|key| Value::STRING(key.as_str().into())).collect()))
+    }
+
+    /// Returns values of a table in alphabetical order of their keys, so they match the result of keys.
+    pub(crate) fn call_values(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
+        let (table,): (Table,) = self.unpack("values", &arguments)?;
+        let mut fields: Vec<(&String, &Value)> = table.iter().collect();
+        fields.sort_by(|first, second| first.0.cmp(second.0));
+        Ok(Value::LIST(fields.into_iter().map(|(_, value)| value.clone()).collect()))
+    }
+
+    /// Declares a record type with fields given as strings (the parser converts identifiers to strings) and name given as name:"Name" (the parser adds the name of the declared variable).
+    pub(crate) fn call_record(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
+        let mut name = "Record".to_string();
+        let mut fields = vec![];
+
+        for argument in arguments {
+            match argument {
+                Value::STRING(field) => fields.push(field.to_string()),
+                Value::KEY_VALUE(key, value) if key == "name" => match *value {
+                    Value::STRING(value) => name = value.to_string(),
+                    value => return self.error(&format!("Name of a record must be a string, but {} was found.", value.text_representation()))
+                },
+                argument => return self.error(&format!("Record requires names of fields and optionally name:\"Name\", but {} was found.", argument.text_representation()))
             }
-        } else {
-            println!("Warning: Failed to convert to number from {}, because it is an unsupported type. Returning 0.", arguments[0].clone().get_type().text_representation());
-            Value::NULL
+        }
+
+        match RecordType::new(name, fields) {
+            Ok(record_type) => Ok(Value::TYPE(Type::RECORD(Rc::new(record_type)))),
+            Err(message) => self.error(&message)
         }
     }
 
-    pub fn call_table(&mut self, arguments: Vec<Value>) -> Value {
-        let mut table: HashMap<String, Value> = HashMap::new();
+    /// Returns a table with fields of all tables. Fields of later tables replace fields of earlier ones.
+    pub(crate) fn call_merge(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
+        if arguments.is_empty() {
+            return self.error("Merge operation requires at least 1 argument.");
+        }
+
+        let mut merged = Table::new();
 
         for argument in arguments {
-            if let Value::KEY_VALUE(key, value) = argument {
-                table.insert(key, *value);
+            if let Value::TABLE(table) = argument {
+                merged.extend(table);
             } else {
-                self.error(&format!("Table operation's all arguments must be key-values, but {} was found.", argument.text_representation()));
+                return self.error(&format!("Merge operation's all arguments must be tables, but {} was found.", argument.text_representation()));
             }
         }
 
-        Value::TABLE(table)
+        Ok(Value::TABLE(merged))
     }
 
-    pub fn call_string(&mut self, arguments: Vec<Value>) -> Value {
-        if arguments.len() != 1 {
-            self.error("String conversion requires 1 argument.");
-        }
-
-        Value::STRING(arguments[0].text_representation())
+    pub(crate) fn call_string(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
+        let (value,): (Value,) = self.unpack("string", &arguments)?;
+        Ok(Value::STRING(value.text_representation().into()))
     }
 
-    pub fn call_length(&mut self, arguments: Vec<Value>) -> Value {
+    pub(crate) fn call_length(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
         if arguments.len() != 1 {
-            self.error("Length operation requires 1 argument that is an array (list or string).");
+            return self.error("Length operation requires 1 argument that is an array (list or string).");
         }
 
         if let Value::LIST(list) = arguments[0].clone() {
-            Value::NUMBER(list.len() as f64)
+            Ok(Value::INTEGER(list.len() as i64))
         } else if let Value::STRING(string) = arguments[0].clone() {
-            Value::NUMBER(string.len() as f64)
+            Ok(Value::INTEGER(string.chars().count() as i64))
         } else {
-            self.error("Length operation requires 1 argument that is an array (list or string).");
+            self.error("Length operation requires 1 argument that is an array (list or string).")
         }
     }
 
-    pub fn call_append(&mut self, arguments: Vec<Value>) -> Value {
+    pub(crate) fn call_append(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
         if arguments.len() != 2 {
-            self.error("Append operation requires 2 arguments: an array (list or string) and a value.");
+            return self.error("Append operation requires 2 arguments: an array (list or string) and a value.");
         }
 
         if let Value::LIST(list) = arguments[0].clone() {
             let mut values = list;
-            values.push(arguments[1].clone());
-            Value::LIST(values)
+            values.push_back(arguments[1].clone());
+            Ok(Value::LIST(values))
         } else if let Value::STRING(string) = arguments[0].clone() {
             if let Value::STRING(new) = arguments[1].clone() {
-                let mut e_string = string;
-                e_string.push_str(&*new);
-                Value::STRING(e_string)
+                let mut e_string = string.to_string();
+                e_string.push_str(&new);
+                Ok(Value::STRING(e_string.into()))
             } else {
-                self.error("Append expected a second string.");
+                self.error("Append expected a second string.")
             }
         } else {
-            self.error("Append operation requires 2 arguments: an array (list or string) and a value.");
+            self.error("Append operation requires 2 arguments: an array (list or string) and a value.")
         }
     }
 
-    pub fn call_remove(&mut self, arguments: Vec<Value>) -> Value {
+    pub(crate) fn call_remove(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
         if arguments.len() == 1 {
             if let Value::LIST(list) = arguments[0].clone() {
                 let mut list = list;
-                list.remove(list.len() - 1);
-                Value::LIST(list)
+
+                if list.pop_back().is_none() {
+                    return self.error("Can't remove the last element of an empty list.");
+                }
+
+                Ok(Value::LIST(list))
             } else if let Value::STRING(string) = arguments[0].clone() {
-                let mut string = string;
-                string.remove(string.len() - 1);
-                Value::STRING(string)
+                let mut string = string.to_string();
+
+                if string.pop().is_none() {
+                    return self.error("Can't remove the last character of an empty string.");
+                }
+
+                Ok(Value::STRING(string.into()))
             } else {
-                self.error("Remove operation requires first argument to be an array (list or string).");
+                self.error("Remove operation requires first argument to be an array (list or string).")
             }
         } else if arguments.len() == 2 {
-            if let Value::NUMBER(index) = arguments[1].clone() {
-                let index = index as usize;
-
+            if arguments[1].is_number() {
                 if let Value::LIST(list) = arguments[0].clone() {
                     let mut list = list;
+                    let index = self.resolve_index(&arguments[1], list.len(), false)?;
                     list.remove(index);
-                    Value::LIST(list)
+                    Ok(Value::LIST(list))
                 } else if let Value::STRING(string) = arguments[0].clone() {
-                    let mut string = string;
-                    string.remove(index);
-                    Value::STRING(string)
+                    let index = self.resolve_index(&arguments[1], string.chars().count(), false)?;
+                    let mut string = string.to_string();
+                    string.remove(char_offset(&string, index));
+                    Ok(Value::STRING(string.into()))
                 } else {
-                    self.error("Remove operation requires first argument to be an array (list or string).");
+                    self.error("Remove operation requires first argument to be an array (list or string).")
                 }
             } else {
-                self.error("Remove operation requires second argument to be a number.");
+                self.error("Remove operation requires second argument to be a number.")
             }
         } else {
-            self.error("Remove operation requires 1 or 2 arguments: an array (list or string) and index (optional, if none, operate on last element).");
+            self.error("Remove operation requires 1 or 2 arguments: an array (list or string) and index (optional, if none, operate on last element).")
         }
     }
 
-    pub fn call_replace(&mut self, arguments: Vec<Value>) -> Value {
+    pub(crate) fn call_replace(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
         if arguments.len() != 3 {
-            self.error("Replace operation requires 3 arguments: an array (list or string), index and value.");
+            return self.error("Replace operation requires 3 arguments: an array (list or string), index and value.");
         }
-        if let Value::NUMBER(index) = arguments[1].clone() {
-            let index = index as usize;
-
+        if arguments[1].is_number() {
             if let Value::LIST(list) = arguments[0].clone() {
                 let mut list = list;
+                let index = self.resolve_index(&arguments[1], list.len(), false)?;
                 list[index] = arguments[2].clone();
-                Value::LIST(list)
+                Ok(Value::LIST(list))
             } else if let Value::STRING(string) = arguments[0].clone() {
                 if let Value::STRING(new) = arguments[2].clone() {
-                    let mut string = string;
-                    string.replace_range(index..(index + 1), &new);
-                    Value::STRING(string)
+                    let index = self.resolve_index(&arguments[1], string.chars().count(), false)?;
+                    let mut string = string.to_string();
+                    let start = char_offset(&string, index);
+                    string.replace_range(start..char_offset(&string, index + 1), &new);
+                    Ok(Value::STRING(string.into()))
                 } else {
-                    self.error("Replace operation requires third argument to be an string if array is a string.");
+                    self.error("Replace operation requires third argument to be an string if array is a string.")
                 }
             } else {
-                self.error("Replace operation requires first argument to be an array (list or string).");
+                self.error("Replace operation requires first argument to be an array (list or string).")
             }
         } else {
-            self.error("Replace operation requires second argument to be a number.");
+            self.error("Replace operation requires second argument to be a number.")
         }
     }
 
-    pub fn call_insert(&mut self, arguments: Vec<Value>) -> Value {
+    pub(crate) fn call_insert(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
         if arguments.len() == 2 {
             if let Value::LIST(list) = arguments[0].clone() {
                 let mut list = list;
-                list.push(arguments[1].clone());
-                Value::LIST(list)
+                list.push_back(arguments[1].clone());
+                Ok(Value::LIST(list))
             } else if let Value::STRING(string) = arguments[0].clone() {
                 if let Value::STRING(appended) = arguments[1].clone() {
-                    let mut string = string;
+                    let mut string = string.to_string();
                     string.push_str(&appended);
-                    Value::STRING(string)
+                    Ok(Value::STRING(string.into()))
                 } else {
-                    self.error("Insert operation requires second argument to be a string when array is a string.");
+                    self.error("Insert operation requires second argument to be a string when array is a string.")
                 }
             } else {
-                self.error("Insert operation requires first argument to be an array (list or string).");
+                self.error("Insert operation requires first argument to be an array (list or string).")
             }
         } else if arguments.len() == 3 {
-            if let Value::NUMBER(index) = arguments[2].clone() {
-                let index = index as usize;
-
+            if arguments[2].is_number() {
                 if let Value::LIST(list) = arguments[0].clone() {
                     let mut list = list;
+                    let index = self.resolve_index(&arguments[2], list.len(), true)?;
                     list.insert(index, arguments[1].clone());
-                    Value::LIST(list)
+                    Ok(Value::LIST(list))
                 } else if let Value::STRING(string) = arguments[0].clone() {
                     if let Value::STRING(appended) = arguments[1].clone() {
-                        let mut string = string;
-                        string.insert_str(index, &appended);
-                        Value::STRING(string)
+                        let index = self.resolve_index(&arguments[2], string.chars().count(), true)?;
+                        let mut string = string.to_string();
+                        string.insert_str(char_offset(&string, index), &appended);
+                        Ok(Value::STRING(string.into()))
                     } else {
-                        self.error("Insert operation requires second argument to be a string when array is a string.");
+                        self.error("Insert operation requires second argument to be a string when array is a string.")
                     }
                 } else {
-                    self.error("Insert operation requires first argument to be an array (list or string).");
+                    self.error("Insert operation requires first argument to be an array (list or string).")
                 }
             } else {
-                self.error("Insert operation requires third argument to be a number.");
+                self.error("Insert operation requires third argument to be a number.")
             }
         } else {
-            self.error("Insert operation requires 2 or 3 arguments: an array (list or string), value and index (optional, if none, operate on last element).");
+            self.error("Insert operation requires 2 or 3 arguments: an array (list or string), value and index (optional, if none, operate on last element).")
+        }
+    }
+
+    pub(crate) fn call_break(&mut self, mut arguments: Vec<Value>) -> Result<Value, HierError> {
+        let label = self.take_label(&mut arguments)?;
+
+        if arguments.len() > 1 {
+            return self.error("Break operation requires 0 or 1 arguments and optionally a label.");
         }
+
+        self.control_flow = Some(ControlFlow::BREAK(label, arguments.into_iter().next().unwrap_or(Value::NULL)));
+
+        Ok(Value::NULL)
     }
 
-    pub fn call_break(&mut self, arguments: Vec<Value>) -> Value {
-        if arguments.len() != 0 {
-            self.error("Break operation requires 0 arguments.");
+    pub(crate) fn call_continue(&mut self, mut arguments: Vec<Value>) -> Result<Value, HierError> {
+        let label = self.take_label(&mut arguments)?;
+
+        if !arguments.is_empty() {
+            return self.error("Continue operation requires 0 arguments and optionally a label.");
         }
 
-        Value::ERROR("LoopExit".to_string())
+        self.control_flow = Some(ControlFlow::CONTINUE(label));
+
+        Ok(Value::NULL)
     }
 
-    pub fn call_brpoint(&mut self, arguments: Vec<Value>) -> Value {
-        if arguments.len() != 0 {
-            self.error("Breakpoint operation requires 0 arguments.");
+    pub(crate) fn call_return(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
+        if arguments.len() > 1 {
+            return self.error("Return operation requires 0 or 1 arguments.");
+        }
+
+        self.control_flow = Some(ControlFlow::RETURN(arguments.into_iter().next().unwrap_or(Value::NULL)));
+
+        Ok(Value::NULL)
+    }
+
+    /// Removes a label (label:"name" key-value) of a loop, a break or a continue from the end of arguments.
+    fn take_label(&self, arguments: &mut Vec<Value>) -> Result<Option<String>, HierError> {
+        if let Some(Value::KEY_VALUE(key, value)) = arguments.last() {
+            if key == "label" {
+                let label = if let Value::STRING(label) = &**value {
+                    label.to_string()
+                } else {
+                    return self.error("Label must be a string.");
+                };
+
+                arguments.pop();
+                return Ok(Some(label));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Handles a signal after an iteration of a loop. Break's value becomes the result of the loop. Returns true if the loop should stop.
+    fn should_exit_loop(&mut self, label: &Option<String>, result: &mut Value) -> bool {
+        let is_targeted = self.control_flow.as_ref().is_some_and(|control_flow| control_flow.targets_loop(label));
+
+        match self.control_flow.take() {
+            None => false,
+            Some(ControlFlow::CONTINUE(_)) if is_targeted => false,
+            Some(ControlFlow::BREAK(_, value)) if is_targeted => {
+                *result = value;
+                true
+            },
+            control_flow => {
+                // Signal is meant for something outside of this loop.
+                self.control_flow = control_flow;
+                true
+            }
+        }
+    }
+
+    pub(crate) fn call_brpoint(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
+        if !arguments.is_empty() {
+            return self.error("Breakpoint operation requires 0 arguments.");
         }
 
         if self.is_debugging {
             debug(self, &String::from("Brpoint"));
         }
 
-        Value::NULL
+        Ok(Value::NULL)
     }
 
-    pub fn call_round(&mut self, arguments: Vec<Value>) -> Value {
-        if arguments.len() != 1 {
-            self.error("Round operation requires 1 number argument.");
+    /// Rounds to the nearest whole number, halves away from 0. Floats become integers if they fit.
+    pub(crate) fn call_round(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
+        let (number,): (Value,) = self.unpack("round", &arguments)?;
+
+        match number {
+            Value::INTEGER(integer) => Ok(Value::INTEGER(integer)),
+            Value::NUMBER(number) if number.is_finite() && number.abs() < i64::MAX as f64 => Ok(Value::INTEGER(number.round() as i64)),
+            Value::NUMBER(number) => Ok(Value::NUMBER(number.round())),
+            Value::BIG_INTEGER(integer) => Ok(Value::BIG_INTEGER(integer)),
+            Value::DECIMAL(decimal) => Ok(Value::DECIMAL(Rc::new(decimal.with_scale_round(0, RoundingMode::HalfUp)))),
+            _ => self.error("Round operation requires a number argument.")
         }
+    }
 
-        if let Value::NUMBER(number) = arguments[0] {
-            Value::NUMBER(number as i64 as f64)
-        } else {
-            self.error("Round operation requires a number argument.");
+    pub(crate) fn call_error(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
+        let mut message: Option<String> = None;
+        let mut kind = "Error".to_string();
+        let mut payload: Option<Value> = None;
+        let mut cause: Option<Value> = None;
+
+        for argument in arguments {
+            match argument {
+                Value::KEY_VALUE(key, value) if key == "kind" => {
+                    if let Value::STRING(value) = *value {
+                        kind = value.to_string();
+                    } else {
+                        return self.error("Error's kind must be a string.");
+                    }
+                },
+                Value::KEY_VALUE(key, value) if key == "payload" => payload = Some(*value),
+                Value::KEY_VALUE(key, value) if key == "cause" => {
+                    if let Value::ERROR(_) = *value {
+                        cause = Some(*value);
+                    } else {
+                        return self.error("Error's cause must be an error.");
+                    }
+                },
+                _ => {
+                    if message.is_some() {
+                        return self.error("Error operation requires 1 argument (and optionally kind, payload and cause key-values).");
+                    }
+
+                    message = Some(argument.text_representation());
+                }
+            }
         }
+
+        let message = if let Some(message) = message {
+            message
+        } else {
+            return self.error("Error operation requires 1 argument (and optionally kind, payload and cause key-values).");
+        };
+
+        // Call of error function itself isn't a part of the trace.
+        let trace = self.call_stack[..self.call_stack.len().saturating_sub(1)].to_vec();
+        let mut error = ErrorValue::new(kind, message, self.current_interpreting_location.clone(), trace);
+        error.payload = payload;
+        error.cause = cause;
+
+        Ok(Value::ERROR(Rc::new(error)))
     }
 
-    pub fn call_error(&mut self, arguments: Vec<Value>) -> Value {
-        if arguments.len() != 1 {
-            self.error("Error operation requires 1 argument.");
+    pub(crate) fn call_panic(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
+        let (message,): (Value,) = self.unpack("panic", &arguments)?;
+        self.error(&format!("Panic: {}", message.text_representation()))
+    }
+
+    pub(crate) fn call_eval(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
+        let (code,): (String,) = self.unpack("eval", &arguments)?;
+
+        if !self.policy.eval {
+            return Ok(self.make_error("PermissionError", "Eval is not allowed.".to_string()));
         }
 
-        Value::ERROR(arguments[0].clone().text_representation())
+        let mut hier = Hier::nested(self);
+        hier.run_code(code)
     }
 
-    pub fn call_panic(&mut self, arguments: Vec<Value>) -> Value {
-        if arguments.len() != 1 {
-            self.error("Panic operation requires 1 argument.");
-        }
+    pub(crate) fn call_stacktrace(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
+        let () = self.unpack("stacktrace", &arguments)?;
 
-        eprintln!("! Panic: {}", arguments[0].clone().text_representation());
-        (self.exit_handler)();
+        // The innermost call is the first one and call of stacktrace function itself is skipped.
+        Ok(Value::LIST(self.call_stack.iter().rev().skip(1).map(|call| Value::STRING(call.text_representation().into())).collect()))
     }
 
-    pub fn call_eval(&mut self, arguments: Vec<Value>) -> Value {
-        if arguments.len() != 1 {
-            self.error("Evaluate operation requires 1 string argument.");
+    /// Splits a string into grapheme clusters: characters as seen by users, like letters with accents or emoji made of many code points.
+    pub(crate) fn call_graphemes(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
+        let (string,): (String,) = self.unpack("graphemes", &arguments)?;
+        Ok(Value::LIST(string.graphemes(true).map(|grapheme| Value::STRING(grapheme.into())).collect()))
+    }
+
+    /// Converts an index of an array (list or string, whose elements are characters) with the length to a position. Negative indices count from the end.
+    /// Position after the last element is valid only when inserting. Indices out of bounds and fractions are errors.
+    fn resolve_index(&self, index: &Value, length: usize, is_inserting: bool) -> Result<usize, HierError> {
+        let number = index.as_number().unwrap_or_default();
+
+        if number.fract() != 0.0 {
+            return self.error(&format!("Index {} must be a whole number.", index.text_representation()));
         }
 
-        if let Value::STRING(code) = arguments[0].clone() {
-            let mut hier = Hier::new(self.path.clone(), self.module_reader, self.exit_handler, false);
-            hier.run(code)
-        } else {
-            self.error("Evaluate operation requires a string argument.");
+        let limit = if is_inserting { length + 1 } else { length } as f64;
+
+        if number >= limit || number < -(length as f64) {
+            return self.error(&format!("Index {} is out of bounds ({} elements).", index.text_representation(), length));
         }
+
+        Ok(if number >= 0.0 { number as usize } else { length - (-number) as usize })
     }
 
-    pub fn call_get(&mut self, arguments: Vec<Value>) -> Value {
+    pub(crate) fn call_get(&mut self, arguments: Vec<Value>) -> Result<Value, HierError> {
         if arguments.len() != 2 && arguments.len() != 1 {
-            self.error("Get operation requires max 2 arguments: object and key (number or string, optional).");
+            return self.error("Get operation requires max 2 arguments: object and key (number or string, optional).");
         }
 
         if arguments.len() == 1 {
-            return arguments[0].clone();
+            return Ok(arguments[0].clone());
         }
 
         if let Value::STRING(property) = arguments[1].clone() {
             match arguments[0].clone() {
                 Value::KEY_VALUE(key, value) => {
-                    if property == "value" {
-                        *value
-                    } else if property == "key" {
-                        Value::STRING(key)
-                    } else {
-                        Value::NULL
-                    }
-                },
-                Value::TABLE(table) => {
-                    if table.contains_key(&property) {
-                        table.get(&property).unwrap().clone()
+                    if &*property == "value" {
+                        Ok(*value)
+                    } else if &*property == "key" {
+                        Ok(Value::STRING(key.into()))
                     } else {
-                        Value::NULL
+                        Ok(Value::NULL)
                     }
                 },
-                _ => Value::NULL
+                Value::TABLE(table) => Ok(find_field(&table, &property).cloned().unwrap_or(Value::NULL)),
+                Value::ERROR(error) => Ok(error.get_property(&property)),
+                Value::USER_DATA(object) => Ok(object.get_property(&property).unwrap_or(Value::NULL)),
+                Value::RECORD(record) => Ok(record.get_property(&property).unwrap_or(Value::NULL)),
+                _ => Ok(Value::NULL)
             }
-        } else if let Value::NUMBER(index) = arguments[1] {
+        } else if let Some(index) = arguments[1].as_number() {
             match arguments[0].clone() {
                 Value::LIST(value) => {
-                    if index >= 0f64 && value.len() <= index as usize || index < 0f64 && value.len() < abs(index) as usize {
-                        self.error(&format!("Index {} is out of bounds ({} elements).", index, value.len()));
-                    }
-
-                    if index >= 0f64 {
-                        value[index as usize].clone()
-                    } else {
-
-                        value[value.len() - (abs(index) as usize)].clone()
-                    }
+                    let index = self.resolve_index(&arguments[1], value.len(), false)?;
+                    Ok(value[index].clone())
                 },
                 Value::STRING(value) => {
-                    if index < 0f64 || value.len() <= index as usize {
-                        self.error(&format!("Index {} is out of bounds ({} elements).", index, value.len()));
-                    }
-                    Value::STRING(value.chars().nth(index as usize).clone().unwrap_or(' ').to_string())
+                    let index = self.resolve_index(&arguments[1], value.chars().count(), false)?;
+                    Ok(Value::STRING(value.chars().nth(index).unwrap_or(' ').to_string().into()))
                 },
-                _ => if index == 0f64 { arguments[0].clone() } else { Value::NULL },
+                _ => if index == 0f64 { Ok(arguments[0].clone()) } else { Ok(Value::NULL) },
             }
         } else {
-            self.error("Get operation requires second arguments to be a number or string.");
+            self.error("Get operation requires second arguments to be a number or string.")
         }
     }
 }
 
-fn abs(n: f64) -> f64 {
-    if n < 0f64 {
-        n * -1f64
-    } else {
-        n
+/// Describes the first syntax error of a module with its location.
+fn syntax_error(errors: &[HierError]) -> String {
+    match errors.first() {
+        Some(error) => format!("{} ({})", error.message, error.location.text_representation()),
+        None => "syntax error.".to_string()
     }
-}
\ No newline at end of file
+}
+
+/// Byte offset of the character at the position, or length of the string if the position is at its end.
+fn char_offset(string: &str, position: usize) -> usize {
+    string.char_indices().nth(position).map_or(string.len(), |(offset, _)| offset)
+}
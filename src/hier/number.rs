@@ -0,0 +1,189 @@
+use std::cmp::Ordering;
+use std::rc::Rc;
+use bigdecimal::BigDecimal;
+use num_bigint::BigInt;
+use num_traits::{ToPrimitive, Zero};
+use crate::hier::value::Value;
+
+/// Arithmetic operation which works on all kinds of numbers.
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    ADDITION,
+    SUBTRACTION,
+    MULTIPLICATION,
+    DIVISION,
+    INTEGER_DIVISION,
+    MODULO
+}
+
+impl Operation {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Operation::ADDITION => "addition",
+            Operation::SUBTRACTION => "subtraction",
+            Operation::MULTIPLICATION => "multiplication",
+            Operation::DIVISION | Operation::INTEGER_DIVISION => "division",
+            Operation::MODULO => "modulo"
+        }
+    }
+}
+
+/// 2 numbers converted to the same kind: the one which can represent both. Integers become big integers, exact numbers become decimals and everything becomes a float.
+/// Decimals are never converted to floats, because they are used when results must be exact.
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+enum Operands {
+    INTEGER(i64, i64),
+    BIG_INTEGER(BigInt, BigInt),
+    DECIMAL(BigDecimal, BigDecimal),
+    FLOAT(f64, f64)
+}
+
+fn rank(value: &Value) -> Option<u8> {
+    match value {
+        Value::INTEGER(_) => Some(0),
+        Value::BIG_INTEGER(_) => Some(1),
+        Value::DECIMAL(_) => Some(2),
+        Value::NUMBER(_) => Some(3),
+        _ => None
+    }
+}
+
+fn to_big_integer(value: &Value) -> BigInt {
+    match value {
+        Value::INTEGER(integer) => BigInt::from(*integer),
+        Value::BIG_INTEGER(integer) => (**integer).clone(),
+        _ => BigInt::zero()
+    }
+}
+
+fn to_decimal(value: &Value) -> BigDecimal {
+    match value {
+        Value::DECIMAL(decimal) => (**decimal).clone(),
+        _ => BigDecimal::from(to_big_integer(value))
+    }
+}
+
+fn promote(operation: Operation, first: &Value, second: &Value) -> Result<Operands, String> {
+    let (first_rank, second_rank) = match (rank(first), rank(second)) {
+        (Some(first_rank), Some(second_rank)) => (first_rank, second_rank),
+        (None, _) => return Err(format!("Argument must be a number in {}. Found {}.", operation.name(), first.text_representation())),
+        (_, None) => return Err(format!("Argument must be a number in {}. Found {}.", operation.name(), second.text_representation()))
+    };
+
+    if (first_rank == 2 && second_rank == 3) || (first_rank == 3 && second_rank == 2) {
+        let (decimal, float) = if first_rank == 2 { (first, second) } else { (second, first) };
+        return Err(format!("Decimal {} can't be used with float {} in {}, because the result wouldn't be exact. Convert the float to a decimal first, for example using (number \"{}d\").", decimal.text_representation(), float.text_representation(), operation.name(), float.text_representation()));
+    }
+
+    Ok(match first_rank.max(second_rank) {
+        0 => Operands::INTEGER(first.as_integer().unwrap_or_default(), second.as_integer().unwrap_or_default()),
+        1 => Operands::BIG_INTEGER(to_big_integer(first), to_big_integer(second)),
+        2 => Operands::DECIMAL(to_decimal(first), to_decimal(second)),
+        _ => Operands::FLOAT(first.as_number().unwrap_or_default(), second.as_number().unwrap_or_default())
+    })
+}
+
+pub fn is_zero(value: &Value) -> bool {
+    match value {
+        Value::INTEGER(integer) => *integer == 0,
+        Value::BIG_INTEGER(integer) => integer.is_zero(),
+        Value::DECIMAL(decimal) => decimal.is_zero(),
+        Value::NUMBER(number) => *number == 0.0,
+        _ => false
+    }
+}
+
+/// Applies an operation to 2 numbers. Integers are computed exactly and their overflow is an error.
+/// Division produces a float, unless one of the numbers is a decimal. Errors are returned as messages.
+pub fn apply(operation: Operation, first: &Value, second: &Value) -> Result<Value, String> {
+    let operands = promote(operation, first, second)?;
+
+    if is_zero(second) {
+        match operation {
+            Operation::DIVISION | Operation::INTEGER_DIVISION => return Err("Dividing by 0 is forbidden.".to_string()),
+            Operation::MODULO => return Err("Modulo by 0 is forbidden.".to_string()),
+            _ => {}
+        }
+    }
+
+    match operands {
+        Operands::INTEGER(integer1, integer2) => {
+            let result = match operation {
+                Operation::ADDITION => integer1.checked_add(integer2),
+                Operation::SUBTRACTION => integer1.checked_sub(integer2),
+                Operation::MULTIPLICATION => integer1.checked_mul(integer2),
+                Operation::DIVISION => return Ok(Value::NUMBER(integer1 as f64 / integer2 as f64)),
+                Operation::INTEGER_DIVISION => integer1.checked_div(integer2),
+                Operation::MODULO => integer1.checked_rem(integer2)
+            };
+
+            match result {
+                Some(integer) => Ok(Value::INTEGER(integer)),
+                None => Err(format!("Integer overflow in {} of {} and {}. Big integers (like {}n) don't overflow.", operation.name(), integer1, integer2, integer1))
+            }
+        },
+        Operands::BIG_INTEGER(integer1, integer2) => Ok(match operation {
+            Operation::ADDITION => Value::BIG_INTEGER(Rc::new(integer1 + integer2)),
+            Operation::SUBTRACTION => Value::BIG_INTEGER(Rc::new(integer1 - integer2)),
+            Operation::MULTIPLICATION => Value::BIG_INTEGER(Rc::new(integer1 * integer2)),
+            Operation::DIVISION => Value::NUMBER(integer1.to_f64().unwrap_or(f64::NAN) / integer2.to_f64().unwrap_or(f64::NAN)),
+            Operation::INTEGER_DIVISION => Value::BIG_INTEGER(Rc::new(integer1 / integer2)),
+            Operation::MODULO => Value::BIG_INTEGER(Rc::new(integer1 % integer2))
+        }),
+        Operands::DECIMAL(decimal1, decimal2) => Ok(Value::DECIMAL(Rc::new(match operation {
+            Operation::ADDITION => decimal1 + decimal2,
+            Operation::SUBTRACTION => decimal1 - decimal2,
+            Operation::MULTIPLICATION => decimal1 * decimal2,
+            Operation::DIVISION => decimal1 / decimal2,
+            Operation::INTEGER_DIVISION => (decimal1 / decimal2).with_scale(0),
+            Operation::MODULO => decimal1 % decimal2
+        }))),
+        Operands::FLOAT(number1, number2) => Ok(Value::NUMBER(match operation {
+            Operation::ADDITION => number1 + number2,
+            Operation::SUBTRACTION => number1 - number2,
+            Operation::MULTIPLICATION => number1 * number2,
+            Operation::DIVISION => number1 / number2,
+            Operation::INTEGER_DIVISION => (number1 / number2).trunc(),
+            Operation::MODULO => number1 % number2
+        }))
+    }
+}
+
+/// Compares 2 numbers, exactly unless one of them is a float. Comparisons with NaN and values which aren't numbers have no ordering.
+pub fn compare(first: &Value, second: &Value) -> Option<Ordering> {
+    match promote(Operation::SUBTRACTION, first, second) {
+        Ok(Operands::INTEGER(integer1, integer2)) => Some(integer1.cmp(&integer2)),
+        Ok(Operands::BIG_INTEGER(integer1, integer2)) => Some(integer1.cmp(&integer2)),
+        Ok(Operands::DECIMAL(decimal1, decimal2)) => Some(decimal1.cmp(&decimal2)),
+        _ => first.as_number()?.partial_cmp(&second.as_number()?)
+    }
+}
+
+/// Parses a number in the format of literals: integers, floats (with .), big integers (with n suffix) and decimals (with d suffix).
+/// Integers which don't fit in 64 bits become big integers.
+pub fn parse(string: &str) -> Option<Value> {
+    if !string.trim_end_matches(['n', 'd']).chars().all(|character| "-0123456789.".contains(character)) {
+        return None;
+    }
+
+    if let Some(digits) = string.strip_suffix('n') {
+        return digits.parse::<BigInt>().ok().map(|integer| Value::BIG_INTEGER(Rc::new(integer)));
+    }
+
+    if let Some(digits) = string.strip_suffix('d') {
+        return digits.parse::<BigDecimal>().ok().map(|decimal| Value::DECIMAL(Rc::new(decimal)));
+    }
+
+    if !string.contains('.') {
+        if let Ok(integer) = string.parse::<i64>() {
+            return Some(Value::INTEGER(integer));
+        }
+
+        if let Ok(integer) = string.parse::<BigInt>() {
+            return Some(Value::BIG_INTEGER(Rc::new(integer)));
+        }
+    }
+
+    string.parse::<f64>().ok().map(Value::NUMBER)
+}
@@ -1,8 +1,10 @@
-use crate::hier::report;
+use std::rc::Rc;
 
 use crate::hier::expression::Expression;
+use crate::hier::hier_error::HierError;
 use crate::hier::interpolated_string::InterpolatedString;
 use crate::hier::location::Location;
+use crate::hier::pattern::{Pattern, PatternValue};
 use crate::hier::token::Token;
 use crate::hier::value::Value;
 
@@ -10,20 +12,17 @@ pub struct Parser {
     pub code: Expression,
     current_index: usize,
     tokens: Vec<Token>,
-    had_error: bool,
-    pub module_reader: fn(String) -> String,
-    pub exit_handler: fn() -> !
+    /// Syntax errors found in the code, in the order they were found.
+    pub errors: Vec<HierError>
 }
 
 impl Parser {
-    pub fn new(tokens: Vec<Token>, module_reader: fn(String) -> String, exit_handler: fn() -> !) -> Self {
+    pub fn new(tokens: Vec<Token>) -> Self {
         Self {
             code: Expression::NUMBER(0.0, Location::empty()),
             current_index: 0,
             tokens,
-            had_error: false,
-            module_reader,
-            exit_handler
+            errors: vec![]
         }
     }
 
@@ -31,7 +30,12 @@ impl Parser {
     pub fn parse(&mut self) -> bool {
         self.code = self.parse_list()[0].clone();
 
-        self.had_error
+        !self.errors.is_empty()
+    }
+
+    /// Reports a syntax error and marks the code as invalid.
+    fn report(&mut self, error: &str, location: Location) {
+        self.errors.push(HierError::new(error.to_string(), location, vec![]));
     }
 
     pub fn parse_list(&mut self) -> Vec<Expression> {
@@ -49,31 +53,40 @@ impl Parser {
                             let loc = (*location).clone();
                             list = Expression::LIST(self.parse_list(), loc);
                         },
-                        _ => report(&format!("Expected ( after !, but {} was found.", current_token.clone()), location.clone())
+                        _ => self.report(&format!("Expected ( after !, but {} was found.", current_token.clone()), location.clone())
                     }
 
                     current_list.push(Expression::BLOCK(vec![list], location.clone()));
                 },
                 Token::LEFT_BRACKET(location) => current_list.push(Expression::LIST(self.parse_list(), location)),
-                Token::RIGHT_BRACKET(_) => return current_list,
+                Token::RIGHT_BRACKET(_) => {
+                    let list = self.desugar_assignment(current_list);
+                    let list = self.desugar_destructuring(list);
+                    let list = self.desugar_parameters(list);
+                    let list = Self::desugar_record(list);
+                    return self.desugar_match(list);
+                },
                 Token::LEFT_CURLY(location) => current_list.push(Expression::BLOCK(self.parse_block(), location)),
-                Token::RIGHT_CURLY(_) => report("Unexpected }.", (*current_token.get_location()).clone()),
-                Token::STRING(string, location) => current_list.push(Expression::STRING(InterpolatedString::construct(string.clone(), Location::empty(), self.module_reader, self.exit_handler), location)),
-                Token::NUMBER(number, location) => current_list.push(Expression::NUMBER(number.clone(), location)),
+                Token::RIGHT_CURLY(_) => self.report("Unexpected }.", (*current_token.get_location()).clone()),
+                Token::STRING(string, location) => current_list.push(Expression::STRING(InterpolatedString::construct(string.clone(), Location::empty()), location)),
+                Token::NUMBER(number, location) => current_list.push(Expression::NUMBER(number, location)),
+                Token::INTEGER(integer, location) => current_list.push(Expression::INTEGER(integer, location)),
+                Token::BIG_INTEGER(integer, location) => current_list.push(Expression::BIG_INTEGER(Rc::new(integer), location)),
+                Token::DECIMAL(decimal, location) => current_list.push(Expression::DECIMAL(Rc::new(decimal), location)),
                 Token::IDENTIFIER(identifier, location) => {
                     let result = self.parse_identifier(identifier, location, &mut current_list, true);
                     current_list.push(result);
                 },
                 Token::DOT(_) => {
                     if let Some(last_expression) = current_list.pop() {
-                        let current_token = self.consume();
+                        let current_token = self.consume().clone();
                         if let Token::IDENTIFIER(identifier, location) = current_token {
                             current_list.push(Expression::PROPERTY(Box::new(last_expression), identifier.to_string(), location.clone()));
                         } else {
-                            report(&format!("Key can only be an identifier, but {} was found.", current_token), (*current_token.get_location()).clone());
+                            self.report(&format!("Key can only be an identifier, but {} was found.", current_token), (*current_token.get_location()).clone());
                         }
                     } else {
-                        report("Dot must be preceded by a expression.", (*current_token.get_location()).clone());
+                        self.report("Dot must be preceded by a expression.", (*current_token.get_location()).clone());
                     }
                 },
                 Token::LEFT_SQUARE(ref location) => {
@@ -86,27 +99,29 @@ impl Parser {
                         } else if let Token::LEFT_BRACKET(location) = current_token {
                             key_expression = Expression::LIST(self.parse_list(), location.clone());
                         } else if let Token::STRING(string, location) = current_token {
-                            key_expression = Expression::STRING(InterpolatedString::construct(string.clone(), Location::empty(), self.module_reader, self.exit_handler), location.clone());
+                            key_expression = Expression::STRING(InterpolatedString::construct(string.clone(), Location::empty()), location.clone());
                         } else if let Token::NUMBER(number, location) = current_token {
-                            key_expression = Expression::NUMBER(number.clone(), location.clone());
+                            key_expression = Expression::NUMBER(number, location.clone());
+                        } else if let Token::INTEGER(integer, location) = current_token {
+                            key_expression = Expression::INTEGER(integer, location.clone());
                         } else if let Token::IDENTIFIER(identifier, location) = current_token {
                             key_expression = Expression::IDENTIFIER(identifier.clone(), location.clone());
                         } else {
-                            report(&format!("Token {} is disallowed in subscript.", current_token), (*current_token.get_location()).clone());
+                            self.report(&format!("Token {} is disallowed in subscript.", current_token), (*current_token.get_location()).clone());
                         }
 
-                        let end = self.consume();
+                        let end = self.consume().clone();
                         if let Token::RIGHT_SQUARE(_) = end { } else {
-                            report("Subscript must end with ].", (*end.get_location()).clone());
+                            self.report("Subscript must end with ].", (*end.get_location()).clone());
                         }
 
                         current_list.push(Expression::LIST(vec![Expression::IDENTIFIER("get".to_string(), location.clone()), last_expression, key_expression], location.clone()))
                     } else {
-                        report("Subscript must be preceded by a expression.", (current_token.clone().get_location()).clone());
+                        self.report("Subscript must be preceded by a expression.", (current_token.clone().get_location()).clone());
                     }
                 },
-                Token::RIGHT_SQUARE(_) => report("Unexpected ].", (*current_token.get_location()).clone()),
-                Token::COLON(_) => report("Unexpected :.", (*current_token.get_location()).clone()),
+                Token::RIGHT_SQUARE(_) => self.report("Unexpected ].", (*current_token.get_location()).clone()),
+                Token::COLON(_) => self.report("Unexpected :.", (*current_token.get_location()).clone()),
             }
         }
 
@@ -128,34 +143,34 @@ impl Parser {
                             let loc = (*location).clone();
                             list = Expression::LIST(self.parse_list(), loc);
                         },
-                        _ => report(&format!("Expected ( after !, but {} was found.", current_token), location.clone())
+                        _ => self.report(&format!("Expected ( after !, but {} was found.", current_token), location.clone())
                     }
 
                     current_list.push(Expression::BLOCK(vec![list], location.clone()));
                 },
                 Token::LEFT_BRACKET(location) => current_list.push(Expression::LIST(self.parse_list(), location)),
-                Token::RIGHT_BRACKET(_) => report("Unexpected ).", (*current_token.get_location()).clone()),
+                Token::RIGHT_BRACKET(_) => self.report("Unexpected ).", (*current_token.get_location()).clone()),
                 Token::LEFT_CURLY(location) => current_list.push(Expression::BLOCK(self.parse_block(), location)),
                 Token::RIGHT_CURLY(_) => return current_list,
-                Token::STRING(string, location) => current_list.push(Expression::STRING(InterpolatedString::construct(string.clone(), Location::empty(), self.module_reader, self.exit_handler), location)),
-                Token::NUMBER(number, location) => current_list.push(Expression::NUMBER(number.clone(), location)),
+                Token::STRING(string, location) => current_list.push(Expression::STRING(InterpolatedString::construct(string.clone(), Location::empty()), location)),
+                Token::NUMBER(number, location) => current_list.push(Expression::NUMBER(number, location)),
+                Token::INTEGER(integer, location) => current_list.push(Expression::INTEGER(integer, location)),
+                Token::BIG_INTEGER(integer, location) => current_list.push(Expression::BIG_INTEGER(Rc::new(integer), location)),
+                Token::DECIMAL(decimal, location) => current_list.push(Expression::DECIMAL(Rc::new(decimal), location)),
                 Token::IDENTIFIER(identifier, location) => {
                     let result = self.parse_identifier(identifier, location, &mut current_list, false);
                     current_list.push(result);
                 },
                 Token::DOT(_) => {
                     if let Some(last_expression) = current_list.pop() {
-                        self.consume();
-
-                        let current_token = Token::DOT(Location::new("".to_string(),0, 0));
-
+                        let current_token = self.consume().clone();
                         if let Token::IDENTIFIER(identifier, location) = current_token {
-                            current_list.push(Expression::PROPERTY(Box::new(last_expression), identifier, location));
+                            current_list.push(Expression::PROPERTY(Box::new(last_expression), identifier.to_string(), location.clone()));
                         } else {
-                            report(&format!("Key can only be an identifier, but {} was found.", current_token), (*current_token.get_location()).clone());
+                            self.report(&format!("Key can only be an identifier, but {} was found.", current_token), (*current_token.get_location()).clone());
                         }
                     } else {
-                        report("Dot must be preceded by a expression.", (*current_token.get_location()).clone());
+                        self.report("Dot must be preceded by a expression.", (*current_token.get_location()).clone());
                     }
                 },
                 Token::LEFT_SQUARE(ref location) => {
@@ -168,27 +183,29 @@ impl Parser {
                         } else if let Token::LEFT_BRACKET(location) = current_token {
                             key_expression = Expression::LIST(self.parse_list(), location.clone());
                         } else if let Token::STRING(string, location) = current_token {
-                            key_expression = Expression::STRING(InterpolatedString::construct(string.clone(), Location::empty(), self.module_reader, self.exit_handler), location.clone());
+                            key_expression = Expression::STRING(InterpolatedString::construct(string.clone(), Location::empty()), location.clone());
                         } else if let Token::NUMBER(number, location) = current_token {
-                            key_expression = Expression::NUMBER(number.clone(), location.clone());
+                            key_expression = Expression::NUMBER(number, location.clone());
+                        } else if let Token::INTEGER(integer, location) = current_token {
+                            key_expression = Expression::INTEGER(integer, location.clone());
                         } else if let Token::IDENTIFIER(identifier, location) = current_token {
                             key_expression = Expression::IDENTIFIER(identifier.clone(), location.clone());
                         } else {
-                            report(&format!("Token {} is disallowed in subscript.", current_token), (*current_token.get_location()).clone());
+                            self.report(&format!("Token {} is disallowed in subscript.", current_token), (*current_token.get_location()).clone());
                         }
 
-                        let end = self.consume();
+                        let end = self.consume().clone();
                         if let Token::RIGHT_SQUARE(_) = end { } else {
-                            report("Subscript must end with ].", (*end.get_location()).clone());
+                            self.report("Subscript must end with ].", (*end.get_location()).clone());
                         }
 
                         current_list.push(Expression::LIST(vec![Expression::IDENTIFIER("get".to_string(), location.clone()), last_expression, key_expression], location.clone()))
                     } else {
-                        report("Subscript must be preceded by a expression.", (current_token.clone().get_location()).clone());
+                        self.report("Subscript must be preceded by a expression.", (current_token.clone().get_location()).clone());
                     }
                 },
-                Token::RIGHT_SQUARE(_) => report("Unexpected ].", (*current_token.get_location()).clone()),
-                Token::COLON(_) => report("Unexpected :.", (*current_token.get_location()).clone()),
+                Token::RIGHT_SQUARE(_) => self.report("Unexpected ].", (*current_token.get_location()).clone()),
+                Token::COLON(_) => self.report("Unexpected :.", (*current_token.get_location()).clone()),
             }
         }
 
@@ -199,18 +216,21 @@ impl Parser {
         let current_token = self.consume().clone();
 
         let expression = match current_token {
-            Token::EXCL_MARK(_) => { report("Unexpected ).", (*current_token.get_location()).clone()); Expression::VALUE(Value::NULL) },
+            Token::EXCL_MARK(_) => { self.report("Unexpected ).", (*current_token.get_location()).clone()); Expression::VALUE(Value::NULL) },
             Token::LEFT_BRACKET(location) => Expression::LIST(self.parse_list(), location),
-            Token::RIGHT_BRACKET(_) => { report("Unexpected ).", (*current_token.get_location()).clone()); Expression::VALUE(Value::NULL) },
+            Token::RIGHT_BRACKET(_) => { self.report("Unexpected ).", (*current_token.get_location()).clone()); Expression::VALUE(Value::NULL) },
             Token::LEFT_CURLY(location) => Expression::BLOCK(self.parse_block(), location),
-            Token::RIGHT_CURLY(_) => { report("Unexpected }.", (*current_token.get_location()).clone()); Expression::VALUE(Value::NULL) },
-            Token::STRING(string, location) => Expression::STRING(InterpolatedString::construct(string.clone(), Location::empty(), self.module_reader, self.exit_handler), location),
-            Token::NUMBER(number, location) => Expression::NUMBER(number.clone(), location),
+            Token::RIGHT_CURLY(_) => { self.report("Unexpected }.", (*current_token.get_location()).clone()); Expression::VALUE(Value::NULL) },
+            Token::STRING(string, location) => Expression::STRING(InterpolatedString::construct(string.clone(), Location::empty()), location),
+            Token::NUMBER(number, location) => Expression::NUMBER(number, location),
+            Token::INTEGER(integer, location) => Expression::INTEGER(integer, location),
+            Token::BIG_INTEGER(integer, location) => Expression::BIG_INTEGER(Rc::new(integer), location),
+            Token::DECIMAL(decimal, location) => Expression::DECIMAL(Rc::new(decimal), location),
             Token::IDENTIFIER(identifier, location) => self.parse_identifier(identifier, location, &mut vec![], false),
-            Token::DOT(_) => { report("Unexpected ..", (*current_token.get_location()).clone()); Expression::VALUE(Value::NULL) },
-            Token::LEFT_SQUARE(_) => { report("Unexpected [.", (*current_token.get_location()).clone()); Expression::VALUE(Value::NULL) },
-            Token::RIGHT_SQUARE(_) => { report("Unexpected ].", (*current_token.get_location()).clone()); Expression::VALUE(Value::NULL) },
-            Token::COLON(_) => { report("Unexpected :.", (*current_token.get_location()).clone()); Expression::VALUE(Value::NULL) },
+            Token::DOT(_) => { self.report("Unexpected ..", (*current_token.get_location()).clone()); Expression::VALUE(Value::NULL) },
+            Token::LEFT_SQUARE(_) => { self.report("Unexpected [.", (*current_token.get_location()).clone()); Expression::VALUE(Value::NULL) },
+            Token::RIGHT_SQUARE(_) => { self.report("Unexpected ].", (*current_token.get_location()).clone()); Expression::VALUE(Value::NULL) },
+            Token::COLON(_) => { self.report("Unexpected :.", (*current_token.get_location()).clone()); Expression::VALUE(Value::NULL) },
         };
 
         expression
@@ -228,22 +248,22 @@ impl Parser {
             The piping syntax is converted into the first example so it has the same effect.
             */
 
-            if current_list.len() == 0 && is_list {
+            if current_list.is_empty() && is_list {
                 // Current list is empty when the identifier is the first element of the list, which means that it should be a name for function > (more than).
                 return Expression::IDENTIFIER(identifier.clone().to_string(), location);
-            } else if current_list.len() == 0 && !is_list {
-                report("Unexpected pipe operator (>). It should be placed after a list.", Location::empty());
+            } else if current_list.is_empty() && !is_list {
+                self.report("Unexpected pipe operator (>). It should be placed after a list.", Location::empty());
                 return Expression::VALUE(Value::NULL);
             }
 
             let last_expression = current_list[current_list.len() - 1].clone();
             current_list.remove(current_list.len() - 1);
 
-            let next_token = self.consume();
+            let next_token = self.consume().clone();
 
             match next_token {
                 Token::LEFT_BRACKET(_) => { }
-                _ => report("There must be a list after the pipe operator (>).", next_token.get_location().clone())
+                _ => self.report("There must be a list after the pipe operator (>).", next_token.get_location().clone())
             }
 
             let mut next_expression = self.parse_list();
@@ -253,7 +273,8 @@ impl Parser {
             Expression::LIST(next_expression, location)
         } else {
             // Check this out
-            if let Token::COLON(_) = self.peek().clone() {
+            // Identifier can be the last token, for example in a REPL line.
+            if let Some(Token::COLON(_)) = self.tokens.get(self.current_index) {
                 self.consume();
                 let value = self.parse_expression();
                 Expression::KEY_VALUE(identifier.to_string().clone(), Box::new(value), location)
@@ -263,7 +284,7 @@ impl Parser {
 
                 let mut list_content: Vec<Expression> = vec![Expression::IDENTIFIER(true_identifier, location.clone())];
 
-                if let Token::LEFT_BRACKET(_) = self.peek() {
+                if let Some(Token::LEFT_BRACKET(_)) = self.tokens.get(self.current_index) {
                     self.consume();
                     list_content.extend(self.parse_list());
                     Expression::LIST(list_content, location)
@@ -276,6 +297,236 @@ impl Parser {
         }
     }
 
+    /// Converts patterns of match into values, because they aren't evaluated, and adds missing guards, so every case has a pattern, a guard and a block.
+    /// For example, (match x (a &rest) if { (> a 0) } { a } _ { 0 }) -> (match x <pattern> { (> a 0) } { a } <pattern> null { 0 }).
+    fn desugar_match(&mut self, list: Vec<Expression>) -> Vec<Expression> {
+        let location = match list.first() {
+            Some(Expression::IDENTIFIER(name, location)) if name == "match" => location.clone(),
+            _ => return list
+        };
+
+        if list.len() < 2 {
+            self.report("Match requires a value and cases.", location);
+            return list;
+        }
+
+        let mut expressions = list.into_iter();
+        let mut result: Vec<Expression> = expressions.by_ref().take(2).collect();
+
+        while let Some(pattern) = expressions.next() {
+            let location = pattern.get_location();
+
+            match Pattern::from_expression(&pattern) {
+                Ok(pattern) => result.push(Expression::VALUE(Value::PATTERN(PatternValue(Rc::new(pattern))))),
+                Err(message) => self.report(&message, location.clone())
+            }
+
+            let mut block = expressions.next();
+
+            if matches!(&block, Some(Expression::IDENTIFIER(name, _)) if name == "if") {
+                match expressions.next() {
+                    Some(guard @ Expression::BLOCK(_, _)) => result.push(guard),
+                    _ => self.report("Guard of a match case must be a block.", location.clone())
+                }
+
+                block = expressions.next();
+            } else {
+                result.push(Expression::VALUE(Value::NULL));
+            }
+
+            match block {
+                Some(block @ Expression::BLOCK(_, _)) => result.push(block),
+                _ => self.report("Case of match must have a block after its pattern.", location)
+            }
+        }
+
+        result
+    }
+
+    /// Converts destructuring declarations and assignments into a declaration (or an assignment) of a pattern, which isn't evaluated.
+    /// For example, (@(a b &rest) list) -> (@ <pattern> list), (=#(host port) config) -> (= <pattern> config) and (@size: s pair) -> (@ <pattern> pair).
+    fn desugar_destructuring(&mut self, list: Vec<Expression>) -> Vec<Expression> {
+        let (operator, target, location) = match list.first() {
+            Some(Expression::LIST(target, location)) => match target.first() {
+                Some(Expression::IDENTIFIER(name, _)) if matches!(name.as_str(), "@" | "=") => (name.clone(), Expression::LIST(target[1..].to_vec(), location.clone()), location.clone()),
+                Some(Expression::IDENTIFIER(name, name_location)) if matches!(name.as_str(), "@#" | "=#") => {
+                    let mut table = vec![Expression::IDENTIFIER("#".to_string(), name_location.clone())];
+                    table.extend_from_slice(&target[1..]);
+                    (name[..1].to_string(), Expression::LIST(table, location.clone()), location.clone())
+                },
+                _ => return list
+            },
+            Some(Expression::KEY_VALUE(key, value, location)) if key.len() > 1 && (key.starts_with('@') || key.starts_with('=')) => {
+                (key[..1].to_string(), Expression::KEY_VALUE(key[1..].to_string(), value.clone(), location.clone()), location.clone())
+            },
+            _ => return list
+        };
+
+        if list.len() != 2 {
+            self.report("Destructuring requires 1 value.", location);
+            return list;
+        }
+
+        let pattern = match Pattern::from_expression(&target) {
+            Ok(pattern) => pattern,
+            Err(message) => {
+                self.report(&message, location);
+                return list;
+            }
+        };
+
+        let mut list = list;
+        let value = list.remove(1);
+
+        vec![Expression::IDENTIFIER(operator, location), Expression::VALUE(Value::PATTERN(PatternValue(Rc::new(pattern)))), value]
+    }
+
+    /// Replaces patterns in parameters of a function with hidden parameters, which are destructured at the beginning of its block.
+    /// For example, ((| (x y) size: s) { ... }) -> ((| <parameter 1> <parameter 2>) { (@ <pattern> <parameter 1>) (@ <pattern> <parameter 2>) ... }).
+    fn desugar_parameters(&mut self, list: Vec<Expression>) -> Vec<Expression> {
+        let mut list = list;
+
+        for i in 1..list.len() {
+            let (Expression::LIST(parameters, _), Expression::BLOCK(block, _)) = (&list[i - 1], &list[i]) else { continue };
+
+            if !matches!(parameters.first(), Some(Expression::IDENTIFIER(name, _)) if name == "|") {
+                continue;
+            }
+
+            let mut parameters = parameters.clone();
+            let mut destructuring = vec![];
+
+            for (index, parameter) in parameters.iter_mut().enumerate().skip(1) {
+                if !matches!(parameter, Expression::LIST(_, _) | Expression::KEY_VALUE(_, _, _)) {
+                    continue;
+                }
+
+                let location = parameter.get_location();
+
+                let pattern = match Pattern::from_expression(parameter) {
+                    Ok(pattern) => pattern,
+                    Err(message) => {
+                        self.report(&message, location);
+                        continue;
+                    }
+                };
+
+                // Identifiers can't contain spaces, so the code can't refer to the parameter.
+                let name = format!("parameter {}", index);
+                destructuring.push(Expression::LIST(vec![Expression::IDENTIFIER("@".to_string(), location.clone()), Expression::VALUE(Value::PATTERN(PatternValue(Rc::new(pattern)))), Expression::IDENTIFIER(name.clone(), location.clone())], location.clone()));
+                *parameter = Expression::IDENTIFIER(name, location);
+            }
+
+            if destructuring.is_empty() {
+                continue;
+            }
+
+            destructuring.extend(block.iter().cloned());
+
+            let (parameters_location, block_location) = (list[i - 1].get_location(), list[i].get_location());
+            list[i - 1] = Expression::LIST(parameters, parameters_location);
+            list[i] = Expression::BLOCK(destructuring, block_location);
+        }
+
+        list
+    }
+
+    /// Converts fields of a record type into strings, because they aren't evaluated, and names the type after the variable it's declared (or assigned) to.
+    /// For example, (@Point (record x y)) -> (@Point (record "x" "y" name:"Point")).
+    fn desugar_record(list: Vec<Expression>) -> Vec<Expression> {
+        let mut list = list;
+
+        if matches!(list.first(), Some(Expression::IDENTIFIER(name, _)) if name == "record") {
+            for expression in list.iter_mut().skip(1) {
+                if let Expression::IDENTIFIER(field, _) = expression {
+                    *expression = Expression::VALUE(Value::STRING(field.as_str().into()));
+                }
+            }
+
+            return list;
+        }
+
+        if let [Expression::IDENTIFIER(variable, _), Expression::LIST(record, location)] = &mut list[..] {
+            let is_named = record.iter().any(|expression| matches!(expression, Expression::KEY_VALUE(key, _, _) if key == "name"));
+
+            if variable.len() > 1 && (variable.starts_with('@') || variable.starts_with('=')) && !is_named
+                && matches!(record.first(), Some(Expression::IDENTIFIER(name, _)) if name == "record") {
+                let name = Expression::VALUE(Value::STRING(variable[1..].into()));
+                record.push(Expression::KEY_VALUE("name".to_string(), Box::new(name), location.clone()));
+            }
+        }
+
+        list
+    }
+
+    /// Converts assignment to a field or an element into assignment of an updated value, so it works like assignment to a variable.
+    /// For example, (=config.port 8080) -> (=config (set config "port" 8080)) and (=list[2] x) -> (=list (set list 2 x)).
+    fn desugar_assignment(&mut self, list: Vec<Expression>) -> Vec<Expression> {
+        let location = match list.first() {
+            Some(target) if Self::is_field_of_assigned(target) => target.get_location().clone(),
+            _ => return list
+        };
+
+        if list.len() != 2 {
+            self.report("Assignment to a field or an element requires 1 value.", location);
+            return list;
+        }
+
+        let mut list = list;
+        let value = list.remove(1);
+        let target = list.remove(0);
+
+        Self::assign_field(target, value)
+    }
+
+    /// Whether the expression is a field (a.b) or an element (a[0]) of a variable being assigned (=a).
+    fn is_field_of_assigned(expression: &Expression) -> bool {
+        match expression {
+            Expression::PROPERTY(object, _, _) => Self::is_assigned(object),
+            Expression::LIST(subscript, _) => matches!(&subscript[..], [Expression::IDENTIFIER(get, _), object, _] if get == "get" && Self::is_assigned(object)),
+            _ => false
+        }
+    }
+
+    fn is_assigned(expression: &Expression) -> bool {
+        match expression {
+            Expression::IDENTIFIER(name, _) => name.len() > 1 && name.starts_with('='),
+            _ => Self::is_field_of_assigned(expression)
+        }
+    }
+
+    /// Builds the assignment from the innermost field outwards, as each field is set in a copy of the object containing it.
+    fn assign_field(target: Expression, value: Expression) -> Vec<Expression> {
+        let (object, key, location) = match target {
+            Expression::PROPERTY(object, key, location) => (*object, Expression::VALUE(Value::STRING(key.into())), location),
+            Expression::LIST(mut subscript, location) if subscript.len() == 3 => {
+                let key = subscript.remove(2);
+                (subscript.remove(1), key, location)
+            },
+            // Variable itself is assigned.
+            target => return vec![target, value]
+        };
+
+        let updated = Expression::LIST(vec![Expression::IDENTIFIER("set".to_string(), location.clone()), Self::read_assigned(object.clone()), key, value], location);
+        Self::assign_field(object, updated)
+    }
+
+    /// Converts a target of assignment into an expression which reads its current value, for example =config.db -> config.db.
+    fn read_assigned(expression: Expression) -> Expression {
+        match expression {
+            Expression::IDENTIFIER(mut name, location) => {
+                name.remove(0);
+                Expression::IDENTIFIER(name, location)
+            },
+            Expression::PROPERTY(object, key, location) => Expression::PROPERTY(Box::new(Self::read_assigned(*object)), key, location),
+            Expression::LIST(mut subscript, location) => {
+                subscript[1] = Self::read_assigned(subscript[1].clone());
+                Expression::LIST(subscript, location)
+            },
+            expression => expression
+        }
+    }
+
     fn consume(&mut self) -> &Token {
         let token = &self.tokens[self.current_index];
         self.current_index += 1;
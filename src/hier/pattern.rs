@@ -0,0 +1,199 @@
+use std::rc::Rc;
+use crate::hier::expression::Expression;
+use crate::hier::types::Type;
+use crate::hier::value::{List, Value};
+
+/// Shape of a value, which can bind its parts to variables. Patterns are made by the parser from expressions, which aren't evaluated:
+/// - _ matches any value,
+/// - identifier matches any value and binds it to a variable,
+/// - name of a type (like Number) matches values of the type. Capitalized names which aren't builtin types (and members of modules) must be variables holding a type, like a record type,
+/// - number, string, true, false and null match equal values,
+/// - list of patterns, like (first second &rest) or (& first second &rest), matches lists whose elements match the patterns. &name binds the rest of elements (& alone ignores them),
+/// - (# key1 key2: pattern) matches tables (and records) having the keys whose values match the patterns (key without a pattern binds the value to a variable named like the key),
+/// - key: pattern matches key values with the key whose value matches the pattern.
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+#[derive(Debug, Clone)]
+pub(crate) enum Pattern {
+    ANY,
+    BIND(String),
+    TYPE(Type),
+    /// Type declared by the code, like a record type, which is read from the variable with this name before matching.
+    NAMED_TYPE(String),
+    LITERAL(Value),
+    /// Patterns of elements before the rest, name of the rest (None if there is no rest) and patterns of elements after it.
+    LIST(Vec<Pattern>, Option<String>, Vec<Pattern>),
+    TABLE(Vec<(String, Pattern)>),
+    KEY_VALUE(String, Box<Pattern>)
+}
+
+/// Pattern as a value, made by the parser for cases of match and destructuring. Its shape is internal to the interpreter.
+#[derive(Debug, Clone)]
+pub struct PatternValue(pub(crate) Rc<Pattern>);
+
+impl Pattern {
+    /// Makes a pattern from an expression. Errors are returned as messages.
+    pub(crate) fn from_expression(expression: &Expression) -> Result<Pattern, String> {
+        let pattern = Self::from_expression_unchecked(expression)?;
+
+        let mut names = pattern.variables();
+        names.sort();
+
+        if let Some(name) = names.windows(2).find(|names| names[0] == names[1]) {
+            return Err(format!("Variable '{}' is bound more than once in a pattern.", name[0]));
+        }
+
+        Ok(pattern)
+    }
+
+    fn from_expression_unchecked(expression: &Expression) -> Result<Pattern, String> {
+        match expression {
+            Expression::IDENTIFIER(name, _) => Ok(match name.as_str() {
+                "_" => Pattern::ANY,
+                "true" => Pattern::LITERAL(Value::BOOL(true)),
+                "false" => Pattern::LITERAL(Value::BOOL(false)),
+                "null" => Pattern::LITERAL(Value::NULL),
+                _ if name.starts_with('&') => return Err(format!("Rest {} can only be used in a list pattern.", name)),
+                _ => match Type::get_for_name(name) {
+                    Some(a_type) => Pattern::TYPE(a_type),
+                    None if name.starts_with(char::is_uppercase) || name.contains("::") => Pattern::NAMED_TYPE(name.clone()),
+                    None => Pattern::BIND(name.clone())
+                }
+            }),
+            Expression::NUMBER(number, _) => Ok(Pattern::LITERAL(Value::NUMBER(*number))),
+            Expression::INTEGER(integer, _) => Ok(Pattern::LITERAL(Value::INTEGER(*integer))),
+            Expression::BIG_INTEGER(integer, _) => Ok(Pattern::LITERAL(Value::BIG_INTEGER(integer.clone()))),
+            Expression::DECIMAL(decimal, _) => Ok(Pattern::LITERAL(Value::DECIMAL(decimal.clone()))),
+            Expression::STRING(string, _) => match string.as_plain() {
+                Some(string) => Ok(Pattern::LITERAL(Value::STRING(string.into()))),
+                None => Err("Strings in patterns can't be interpolated.".to_string())
+            },
+            Expression::KEY_VALUE(key, value, _) => Ok(Pattern::KEY_VALUE(key.clone(), Box::new(Self::from_expression_unchecked(value)?))),
+            Expression::LIST(expressions, _) => match expressions.first() {
+                Some(Expression::IDENTIFIER(name, _)) if name == "#" || name == "table" => Self::table_from_expressions(&expressions[1..]),
+                Some(Expression::IDENTIFIER(name, _)) if name == "&" || name == "list" => Self::list_from_expressions(&expressions[1..]),
+                _ => Self::list_from_expressions(expressions)
+            },
+            _ => Err(format!("{} isn't a valid pattern.", expression.get_representation()))
+        }
+    }
+
+    fn list_from_expressions(expressions: &[Expression]) -> Result<Pattern, String> {
+        let mut before = vec![];
+        let mut rest = None;
+        let mut after = vec![];
+
+        for expression in expressions {
+            match expression {
+                Expression::IDENTIFIER(name, _) if name.starts_with('&') => {
+                    if rest.is_some() {
+                        return Err("List pattern can have only 1 rest.".to_string());
+                    }
+
+                    rest = Some(name[1..].to_string());
+                },
+                _ if rest.is_some() => after.push(Self::from_expression_unchecked(expression)?),
+                _ => before.push(Self::from_expression_unchecked(expression)?)
+            }
+        }
+
+        Ok(Pattern::LIST(before, rest, after))
+    }
+
+    fn table_from_expressions(expressions: &[Expression]) -> Result<Pattern, String> {
+        let mut fields = vec![];
+
+        for expression in expressions {
+            match expression {
+                Expression::IDENTIFIER(name, _) => fields.push((name.clone(), Pattern::BIND(name.clone()))),
+                Expression::KEY_VALUE(key, value, _) => fields.push((key.clone(), Self::from_expression_unchecked(value)?)),
+                _ => return Err(format!("Table pattern can contain only keys and key: pattern pairs, but {} was found.", expression.get_representation()))
+            }
+        }
+
+        Ok(Pattern::TABLE(fields))
+    }
+
+    /// Names of variables bound by the pattern.
+    pub(crate) fn variables(&self) -> Vec<String> {
+        match self {
+            Pattern::BIND(name) => vec![name.clone()],
+            Pattern::LIST(before, rest, after) => {
+                let mut names: Vec<String> = before.iter().chain(after).flat_map(Pattern::variables).collect();
+                names.extend(rest.iter().filter(|rest| !rest.is_empty()).cloned());
+                names
+            },
+            Pattern::TABLE(fields) => fields.iter().flat_map(|(_, pattern)| pattern.variables()).collect(),
+            Pattern::KEY_VALUE(_, pattern) => pattern.variables(),
+            _ => vec![]
+        }
+    }
+
+    /// Replaces names of types declared by the code with the types, using a function which reads variables.
+    pub(crate) fn resolve(&self, get: &mut dyn FnMut(&str) -> Value) -> Result<Pattern, String> {
+        let resolve_all = |patterns: &[Pattern], get: &mut dyn FnMut(&str) -> Value| patterns.iter().map(|pattern| pattern.resolve(get)).collect::<Result<Vec<Pattern>, String>>();
+
+        Ok(match self {
+            Pattern::NAMED_TYPE(name) => match get(name) {
+                Value::TYPE(a_type) => Pattern::TYPE(a_type),
+                value => return Err(format!("{} in a pattern must be a type (like a record type), but it's {}. Names of variables bound by patterns can't be capitalized.", name, value.text_representation()))
+            },
+            Pattern::LIST(before, rest, after) => Pattern::LIST(resolve_all(before, get)?, rest.clone(), resolve_all(after, get)?),
+            Pattern::TABLE(fields) => Pattern::TABLE(fields.iter().map(|(key, pattern)| Ok((key.clone(), pattern.resolve(get)?))).collect::<Result<_, String>>()?),
+            Pattern::KEY_VALUE(key, pattern) => Pattern::KEY_VALUE(key.clone(), Box::new(pattern.resolve(get)?)),
+            pattern => pattern.clone()
+        })
+    }
+
+    /// Checks whether the value matches. Named types must be resolved first, because they never match. Values of variables are added to bindings, which can be incomplete if the value doesn't match.
+    pub(crate) fn matches(&self, value: &Value, bindings: &mut Vec<(String, Value)>) -> bool {
+        match self {
+            Pattern::ANY => true,
+            Pattern::BIND(name) => {
+                bindings.push((name.clone(), value.clone()));
+                true
+            },
+            Pattern::TYPE(a_type) => value.is_of_type(a_type),
+            Pattern::NAMED_TYPE(_) => false,
+            Pattern::LITERAL(literal) => (literal.get_type() == value.get_type() || (literal.is_number() && value.is_number())) && literal == value,
+            Pattern::LIST(before, rest, after) => {
+                let Value::LIST(list) = value else { return false };
+
+                let fits = match rest {
+                    Some(_) => list.len() >= before.len() + after.len(),
+                    None => list.len() == before.len()
+                };
+
+                if !fits {
+                    return false;
+                }
+
+                let rest_end = list.len() - after.len();
+
+                if !before.iter().zip(list.iter()).all(|(pattern, element)| pattern.matches(element, bindings)) {
+                    return false;
+                }
+
+                if !after.iter().zip(list.iter().skip(rest_end)).all(|(pattern, element)| pattern.matches(element, bindings)) {
+                    return false;
+                }
+
+                if let Some(name) = rest.as_ref().filter(|name| !name.is_empty()) {
+                    let elements: List = list.clone().slice(before.len()..rest_end);
+                    bindings.push((name.clone(), Value::LIST(elements)));
+                }
+
+                true
+            },
+            Pattern::TABLE(fields) => fields.iter().all(|(key, pattern)| {
+                let field = match value {
+                    Value::TABLE(table) => table.get(key).cloned(),
+                    Value::RECORD(record) => record.get_property(key),
+                    _ => None
+                };
+
+                field.is_some_and(|field| pattern.matches(&field, bindings))
+            }),
+            Pattern::KEY_VALUE(key, pattern) => matches!(value, Value::KEY_VALUE(value_key, field) if value_key == key && pattern.matches(field, bindings))
+        }
+    }
+}
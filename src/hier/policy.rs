@@ -0,0 +1,94 @@
+use std::path::{Component, Path, PathBuf};
+
+/// Operations which the code is allowed to do. Operations which aren't allowed produce errors of kind PermissionError, which can be handled using try.
+/// Default policy denies everything.
+#[derive(Debug, Clone, Default)]
+pub struct Policy {
+    /// Whether processes can be run.
+    pub process: bool,
+    /// Directories whose files (including subdirectories) can be read.
+    pub read: Vec<PathBuf>,
+    /// Directories whose files (including subdirectories) can be written.
+    pub write: Vec<PathBuf>,
+    /// Whether modules outside of the directory of the main module can be imported.
+    pub imports_outside_root: bool,
+    /// Whether eval can be used.
+    pub eval: bool
+}
+
+impl Policy {
+    /// Policy which allows everything, for trusted code.
+    pub fn allow_all() -> Self {
+        Self {
+            process: true,
+            read: vec![PathBuf::from("/")],
+            write: vec![PathBuf::from("/")],
+            imports_outside_root: true,
+            eval: true
+        }
+    }
+
+    pub fn can_read(&self, path: &str) -> bool {
+        Self::is_inside_any(path, &self.read)
+    }
+
+    pub fn can_write(&self, path: &str) -> bool {
+        Self::is_inside_any(path, &self.write)
+    }
+
+    /// Whether a module can be imported by the code whose main module is in the root directory.
+    pub fn can_import(&self, path: &str, root: &str) -> bool {
+        self.imports_outside_root || Self::is_inside(path, Path::new(root))
+    }
+
+    fn is_inside_any(path: &str, directories: &[PathBuf]) -> bool {
+        directories.iter().any(|directory| Self::is_inside(path, directory))
+    }
+
+    fn is_inside(path: &str, directory: &Path) -> bool {
+        match (resolve(Path::new(path)), resolve(directory)) {
+            (Some(path), Some(directory)) => path.starts_with(directory),
+            _ => false
+        }
+    }
+}
+
+/// Makes a path absolute and resolves symlinks, . and .. in it, so it can't leave a directory unnoticed.
+/// Only the longest existing part of the path is resolved by the system, so files which will be created (and their directories) don't need to exist.
+/// Returns None for paths with a broken symlink, because it's unknown where a file created through it would be.
+fn resolve(path: &Path) -> Option<PathBuf> {
+    let path = absolute(path);
+
+    for existing in path.ancestors() {
+        match existing.canonicalize() {
+            Ok(canonical) => return Some(normalize(&canonical.join(path.strip_prefix(existing).ok()?))),
+            Err(_) if existing.symlink_metadata().is_ok() => return None,
+            Err(_) => {}
+        }
+    }
+
+    Some(normalize(&path))
+}
+
+fn absolute(path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().unwrap_or_default().join(path)
+    }
+}
+
+/// Removes . and .. from an absolute path without accessing the file system.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {},
+            Component::ParentDir => { normalized.pop(); },
+            component => normalized.push(component)
+        }
+    }
+
+    normalized
+}
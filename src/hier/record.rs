@@ -0,0 +1,114 @@
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use crate::hier::value::Value;
+
+/// Source of identities of record types.
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Type of records declared by the code, for example (@Point (record x y)). Records of the type have exactly these fields.
+/// Every declaration is a different type, even if it has the same name and fields, so types are compared by their identity.
+#[derive(Debug, Clone)]
+pub struct RecordType {
+    pub name: String,
+    pub fields: Vec<String>,
+    id: u64
+}
+
+impl RecordType {
+    pub(crate) fn new(name: String, fields: Vec<String>) -> Result<Self, String> {
+        for (i, field) in fields.iter().enumerate() {
+            if field.is_empty() {
+                return Err(format!("Fields of record {} must have names.", name));
+            }
+
+            if fields[..i].contains(field) {
+                return Err(format!("Record {} has field {} more than once.", name, field));
+            }
+        }
+
+        Ok(Self { name, fields, id: NEXT_ID.fetch_add(1, Ordering::Relaxed) })
+    }
+
+    fn index_of(&self, field: &str) -> Option<usize> {
+        self.fields.iter().position(|name| name == field)
+    }
+}
+
+impl PartialEq for RecordType {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for RecordType {
+
+}
+
+impl Hash for RecordType {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+/// Instance of a record type. Values are in the order of fields of the type.
+#[derive(Debug, Clone)]
+pub struct Record {
+    pub record_type: Rc<RecordType>,
+    pub values: Vec<Value>
+}
+
+impl Record {
+    /// Makes a record from values of all fields, given either in the order of fields, like (Point 1 2), or as key-values, like (Point y:2 x:1).
+    pub(crate) fn construct(record_type: Rc<RecordType>, arguments: Vec<Value>) -> Result<Self, String> {
+        let name = &record_type.name;
+
+        if !matches!(arguments.first(), Some(Value::KEY_VALUE(_, _))) {
+            if arguments.len() != record_type.fields.len() {
+                return Err(format!("Record {} has {} fields ({}), but {} values were provided.", name, record_type.fields.len(), record_type.fields.join(" "), arguments.len()));
+            }
+
+            return Ok(Self { record_type, values: arguments });
+        }
+
+        let mut values: Vec<Option<Value>> = vec![None; record_type.fields.len()];
+
+        for argument in arguments {
+            let Value::KEY_VALUE(key, value) = argument else {
+                return Err(format!("Values of fields of record {} must be either all key-values or all in the order of fields, but {} was found among key-values.", name, argument.text_representation()));
+            };
+
+            match record_type.index_of(&key) {
+                Some(index) if values[index].is_some() => return Err(format!("Field {} of record {} was provided more than once.", key, name)),
+                Some(index) => values[index] = Some(*value),
+                None => return Err(format!("Record {} doesn't have field {}.", name, key))
+            }
+        }
+
+        if let Some(index) = values.iter().position(Option::is_none) {
+            return Err(format!("Field {} of record {} is missing.", record_type.fields[index], name));
+        }
+
+        Ok(Self { record_type, values: values.into_iter().flatten().collect() })
+    }
+
+    pub fn get_property(&self, property: &str) -> Option<Value> {
+        self.record_type.index_of(property).map(|index| self.values[index].clone())
+    }
+
+    /// Returns a copy of the record with a new value of the field. Fields can't be added, so it's an error if the field doesn't exist.
+    pub(crate) fn with_property(&self, property: &str, value: Value) -> Result<Self, String> {
+        let Some(index) = self.record_type.index_of(property) else {
+            return Err(format!("Record {} doesn't have field {}.", self.record_type.name, property));
+        };
+
+        let mut record = self.clone();
+        record.values[index] = value;
+        Ok(record)
+    }
+
+    pub fn text_representation(&self) -> String {
+        let fields: Vec<String> = self.record_type.fields.iter().zip(&self.values).map(|(field, value)| format!("{}:{}", field, value.text_representation())).collect();
+        format!("{}({})", self.record_type.name, fields.join(" "))
+    }
+}
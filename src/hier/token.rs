@@ -1,8 +1,10 @@
 use core::fmt;
 use std::fmt::{Debug, Formatter};
+use bigdecimal::BigDecimal;
+use num_bigint::BigInt;
 use crate::hier::location::Location;
 
-#[allow(non_camel_case_types)]
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
 #[derive(Debug, Clone)]
 pub enum Token {
     LEFT_BRACKET(Location),
@@ -12,6 +14,9 @@ pub enum Token {
     LEFT_SQUARE(Location),
     RIGHT_SQUARE(Location),
     NUMBER(f64, Location),
+    INTEGER(i64, Location),
+    BIG_INTEGER(BigInt, Location),
+    DECIMAL(BigDecimal, Location),
     STRING(String, Location),
     IDENTIFIER(String, Location),
     DOT(Location),
@@ -28,6 +33,9 @@ impl Token {
             Token::LEFT_CURLY(location) => location,
             Token::RIGHT_CURLY(location) => location,
             Token::NUMBER(_, location) => location,
+            Token::INTEGER(_, location) => location,
+            Token::BIG_INTEGER(_, location) => location,
+            Token::DECIMAL(_, location) => location,
             Token::STRING(_, location) => location,
             Token::IDENTIFIER(_, location) => location,
             Token::DOT(location) => location,
@@ -47,6 +55,9 @@ impl Token {
             Token::LEFT_SQUARE(_) => "[".to_string(),
             Token::RIGHT_SQUARE(_) => "]".to_string(),
             Token::NUMBER(number, _) => number.to_string(),
+            Token::INTEGER(integer, _) => integer.to_string(),
+            Token::BIG_INTEGER(integer, _) => integer.to_string() + "n",
+            Token::DECIMAL(decimal, _) => decimal.to_plain_string() + "d",
             Token::STRING(string, _) => "\"".to_string() + string + "\"",
             Token::IDENTIFIER(identifier, _) => identifier.to_string(),
             Token::DOT(_) => ".".to_string(),
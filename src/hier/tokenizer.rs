@@ -1,42 +1,51 @@
+use bigdecimal::BigDecimal;
+use num_bigint::BigInt;
 use crate::hier::location::Location;
-use crate::hier::report;
+use crate::hier::hier_error::HierError;
 use crate::hier::token::Token;
 
 pub struct Tokenizer {
-    code: String,
+    /// Characters of the code, so they can be indexed in constant time.
+    code: Vec<char>,
     current_index: usize,
     current_line: i64,
     current_offset: i64,
     pub tokens: Vec<Token>,
-    had_error: bool,
+    /// Syntax errors found in the code, in the order they were found.
+    pub errors: Vec<HierError>,
     pub module_name: String
 }
 
 impl Tokenizer {
     pub fn new(code: String) -> Self {
         Self {
-            code,
+            code: code.chars().collect(),
             current_index: 0,
             current_line: 1,
             current_offset: 0,
             tokens: vec![],
-            had_error: false,
+            errors: vec![],
             module_name: "main".to_string(),
         }
     }
 
     pub fn new_with_name(code: String, module_name: String) -> Self {
         Self {
-            code,
+            code: code.chars().collect(),
             current_index: 0,
             current_line: 1,
             current_offset: 0,
             tokens: vec![],
-            had_error: false,
+            errors: vec![],
             module_name
         }
     }
 
+    /// Records a syntax error at the current location.
+    fn report(&mut self, error: &str) {
+        self.errors.push(HierError::new(error.to_string(), self.make_location(), vec![]));
+    }
+
     /// Returns bool if there was a error.
     pub fn tokenize_module(&mut self) -> bool {
         self.tokens.push(Token::LEFT_CURLY(self.make_location()));
@@ -45,7 +54,7 @@ impl Tokenizer {
 
         self.tokens.push(Token::RIGHT_CURLY(self.make_location()));
 
-        self.had_error
+        !self.errors.is_empty()
     }
 
     pub fn tokenize_code(&mut self) -> bool {
@@ -96,7 +105,7 @@ impl Tokenizer {
                 count_of_brackets -= 1;
 
                 if count_of_brackets == -1 {
-                    report("Unexpected ).", self.make_location());
+                    self.report("Unexpected ).");
                 }
 
                 self.consume();
@@ -110,7 +119,7 @@ impl Tokenizer {
                 count_of_squares -= 1;
 
                 if count_of_squares == -1 {
-                    report("Unexpected ].", self.make_location());
+                    self.report("Unexpected ].");
                 }
 
                 self.consume();
@@ -124,7 +133,7 @@ impl Tokenizer {
                 count_of_curlys -= 1;
 
                 if count_of_curlys == -1 {
-                    report("Unexpected }.", self.make_location());
+                    self.report("Unexpected }.");
                 }
 
                 self.consume();
@@ -140,14 +149,14 @@ impl Tokenizer {
         }
 
         if count_of_curlys != 0 {
-            report("Missing }", self.make_location());
+            self.report("Missing }");
         } else if count_of_brackets != 0 {
-            report("Missing )", self.make_location());
+            self.report("Missing )");
         } else if count_of_squares != 0 {
-            report("Missing ]", self.make_location());
+            self.report("Missing ]");
         }
 
-        self.had_error
+        !self.errors.is_empty()
     }
 
     pub fn tokenize_interpolation(&mut self) -> usize {
@@ -304,30 +313,37 @@ impl Tokenizer {
 
         let mut had_error = false;
         let mut is_first_character = true;
-
+        // n for big integers and d for decimals.
+        let mut suffix = None;
 
         while self.current_index < self.code.len() && self.peek() != ')' && self.peek() != ',' && self.peek() != '(' && self.peek() != ' ' && self.peek() != '\n' && self.peek() != ']' {
             if had_error {
+                self.consume();
                 continue;
             }
 
             let current_char = self.consume();
-            if Tokenizer::is_a_digit(current_char) {
+            if suffix.is_some() {
+                self.report("Suffix (n or d) can be only present at the end of the number.");
+                had_error = true;
+            } else if Tokenizer::is_a_digit(current_char) {
                 if !is_first_character && current_char == '-' {
-                    report("- sign can be only present at the beginning of the number.", self.make_location());
+                    self.report("- sign can be only present at the beginning of the number.");
                     had_error = true;
                 }
 
                 if is_first_character && current_char == '.' {
-                    report(". must not be present at the beginning of the number.", self.make_location());
+                    self.report(". must not be present at the beginning of the number.");
                     had_error = true;
                 }
 
                 number_string.push(current_char);
             } else if current_char == '(' {
 
+            } else if (current_char == 'n' || current_char == 'd') && number_string.chars().any(|character| character.is_ascii_digit()) {
+                suffix = Some(current_char);
             } else {
-                report(&format!("Character {} is disallowed in numbers. Only . - 0 1 2 3 4 5 6 7 8 9 characters are allowed.", current_char), self.make_location());
+                self.report(&format!("Character {} is disallowed in numbers. Only . - 0 1 2 3 4 5 6 7 8 9 characters and n or d suffix are allowed.", current_char));
                 had_error = true;
             }
 
@@ -344,18 +360,36 @@ impl Tokenizer {
             }
         }
 
-        if !had_error {
+        if !had_error && suffix == Some('n') {
+            match number_string.parse::<BigInt>() {
+                Ok(integer) => self.tokens.push(Token::BIG_INTEGER(integer, self.make_location())),
+                Err(_) => {
+                    self.report(&format!("Big integer {}n must have -?[0123456789]+n format.", number_string));
+                }
+            }
+        } else if !had_error && suffix == Some('d') {
+            match number_string.parse::<BigDecimal>() {
+                Ok(decimal) => self.tokens.push(Token::DECIMAL(decimal, self.make_location())),
+                Err(_) => {
+                    self.report(&format!("Decimal {}d must have -?[0123456789]+(.[0123456789]+)?d format.", number_string));
+                }
+            }
+        } else if !had_error && !number_string.contains('.') {
+            match number_string.parse::<i64>() {
+                Ok(integer) => self.tokens.push(Token::INTEGER(integer, self.make_location())),
+                Err(_) => {
+                    self.report(&format!("Integer {} is too large. Integers must be between {} and {}.", number_string, i64::MIN, i64::MAX));
+                }
+            }
+        } else if !had_error {
             let number = number_string.parse::<f64>();
 
             match number {
                 Ok(number) => self.tokens.push(Token::NUMBER(number, self.make_location())),
                 Err(_) => {
-                    report(&format!("Number {} must have -?[0123456789]+(.[0123456789]+)? format.", number_string), self.make_location());
-                    self.had_error = true;
+                    self.report(&format!("Number {} must have -?[0123456789]+(.[0123456789]+)? format.", number_string));
                 }
             }
-        } else {
-            self.had_error = true;
         }
     }
 
@@ -376,8 +410,7 @@ impl Tokenizer {
         }
 
         if self.consume() != '"' {
-            report("Unterminated string.", self.make_location());
-            self.had_error = true;
+            self.report("Unterminated string.");
         }
 
         self.tokens.push(Token::STRING(string, self.make_location()));
@@ -392,15 +425,15 @@ impl Tokenizer {
     }
 
     fn peek(&self) -> char {
-        self.code.chars().nth(self.current_index).unwrap_or(' ')
+        self.code.get(self.current_index).copied().unwrap_or(' ')
     }
 
     fn peek_next(&self) -> char {
-        self.code.chars().nth(self.current_index + 1).unwrap_or(' ')
+        self.code.get(self.current_index + 1).copied().unwrap_or(' ')
     }
 
     fn consume(&mut self) -> char {
-        let char = self.code.chars().nth(self.current_index).unwrap_or(' ');
+        let char = self.code.get(self.current_index).copied().unwrap_or(' ');
         self.current_index += 1;
         self.current_offset += 1;
         char
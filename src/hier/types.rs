@@ -1,9 +1,15 @@
-#[allow(non_camel_case_types)]
+use std::rc::Rc;
+use crate::hier::record::RecordType;
+
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
 #[derive(Clone, Eq, PartialEq, Debug, Hash)]
 pub enum Type {
     LIST,
     STRING,
     NUMBER,
+    INTEGER,
+    BIG_INTEGER,
+    DECIMAL,
     FUNCTION,
     BOOL,
     NULL,
@@ -13,7 +19,12 @@ pub enum Type {
     KEY_VALUE,
     TABLE,
     ERROR,
-    ENVIRONMENT
+    ENVIRONMENT,
+    PATTERN,
+    /// Type of a host object with its name.
+    USER_DATA(String),
+    /// Record type declared by the code.
+    RECORD(Rc<RecordType>)
 }
 
 impl Type {
@@ -21,6 +32,9 @@ impl Type {
         match self {
             Type::STRING => "String".to_string(),
             Type::NUMBER => "Number".to_string(),
+            Type::INTEGER => "Integer".to_string(),
+            Type::BIG_INTEGER => "BigInteger".to_string(),
+            Type::DECIMAL => "Decimal".to_string(),
             Type::BOOL => "Bool".to_string(),
             Type::NULL => "Null".to_string(),
             Type::LIST => "List".to_string(),
@@ -32,13 +46,19 @@ impl Type {
             Type::TABLE => "Table".to_string(),
             Type::ERROR => "Error".to_string(),
             Type::ENVIRONMENT => "Environment".to_string(),
+            Type::PATTERN => "Pattern".to_string(),
+            Type::USER_DATA(name) => name.clone(),
+            Type::RECORD(record_type) => record_type.name.clone(),
         }
     }
 
-    pub fn get_for_name(name: &String) -> Option<Type> {
-        match name as &str {
+    pub fn get_for_name(name: &str) -> Option<Type> {
+        match name {
             "String" => Some(Type::STRING),
             "Number" => Some(Type::NUMBER),
+            "Integer" => Some(Type::INTEGER),
+            "BigInteger" => Some(Type::BIG_INTEGER),
+            "Decimal" => Some(Type::DECIMAL),
             "Bool" => Some(Type::BOOL),
             "Null" => Some(Type::NULL),
             "List" => Some(Type::LIST),
@@ -50,6 +70,7 @@ impl Type {
             "Table" => Some(Type::TABLE),
             "Error" => Some(Type::ERROR),
             "Environment" => Some(Type::ENVIRONMENT),
+            "Pattern" => Some(Type::PATTERN),
             _ => None,
         }
     }
@@ -0,0 +1,33 @@
+use std::any::Any;
+use crate::hier::environment::Environment;
+use crate::hier::hier_error::HierError;
+use crate::hier::value::Value;
+
+/// Object of the host (like a file handle or a parsed document), which can be passed to Hier code as a value.
+/// It's reference-counted, so to change its state use a Cell or a RefCell.
+pub trait UserData: Any {
+    /// Name of the type, reported by is and get_type.
+    fn type_name(&self) -> String;
+
+    /// Value of a property, read using get or object.property. None if there is no such property.
+    fn get_property(&self, _name: &str) -> Option<Value> {
+        None
+    }
+
+    /// Whether the object has a method, which is called using (object.method arguments).
+    fn has_method(&self, _name: &str) -> bool {
+        false
+    }
+
+    /// Calls a method with arguments (without the object). It's called only if has_method returned true.
+    fn call_method(&self, environment: &mut Environment, name: &str, _arguments: Vec<Value>) -> Result<Value, HierError> {
+        environment.error(&format!("{} doesn't have a method {}.", self.type_name(), name))
+    }
+}
+
+impl dyn UserData {
+    /// Returns the object as its concrete type, if it has this type.
+    pub fn downcast_ref<T: UserData>(&self) -> Option<&T> {
+        (self as &dyn Any).downcast_ref()
+    }
+}
@@ -1,26 +1,69 @@
-use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
+use bigdecimal::BigDecimal;
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+use crate::hier::block::Block;
+use std::rc::Rc;
 use crate::hier::environment::Environment;
-use crate::hier::expression::Expression;
+use crate::hier::error_value::ErrorValue;
+use crate::hier::function::Function;
+use crate::hier::hier_error::HierError;
+use crate::hier::number;
+use crate::hier::pattern::PatternValue;
+use crate::hier::record::Record;
 use crate::hier::types::Type;
+use crate::hier::user_data::UserData;
 
-#[allow(non_camel_case_types)]
+/// List of values. It's a persistent vector, so copies share elements and appending to a copy doesn't copy the whole list.
+pub type List = im_rc::Vector<Value>;
+/// Fields of a table. Like lists, copies of tables share their fields.
+pub type Table = im_rc::HashMap<String, Value>;
+
+/// Finds a field of a table. If the table doesn't have it, it's looked up in the table's prototype (a table in its prototype field), then in the prototype's prototype and so on.
+pub fn find_field<'a>(table: &'a Table, key: &str) -> Option<&'a Value> {
+    match (table.get(key), table.get("prototype")) {
+        (Some(value), _) => Some(value),
+        (None, Some(Value::TABLE(prototype))) => find_field(prototype, key),
+        _ => None
+    }
+}
+
+/// Function implemented in Rust. It can capture state of the host.
+pub type NativeFunction = Rc<dyn Fn(&mut Environment, Vec<Value>) -> Result<Value, HierError>>;
+
+/// Value used by the code. More kinds of values can be added, so matching them outside of the crate requires a wildcard arm.
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
 #[derive(Clone)]
+#[non_exhaustive]
 pub enum Value {
-    LIST(Vec<Value>),
-    STRING(String),
+    LIST(List),
+    STRING(Rc<str>),
     NUMBER(f64),
-    FUNCTION(Vec<String>, Box<Value>),
-    NATIVE_FUNCTION(fn(&mut Environment, Vec<Value>) -> Value, i64),
+    /// Whole number with exact arithmetic. Operations which overflow it are errors.
+    INTEGER(i64),
+    /// Integer of any size.
+    BIG_INTEGER(Rc<BigInt>),
+    /// Exact decimal number, for example for money. It keeps its digits after the point, so 1.50 stays 1.50.
+    DECIMAL(Rc<BigDecimal>),
+    /// Function defined by the code.
+    FUNCTION(Rc<Function>),
+    NATIVE_FUNCTION(NativeFunction, i64),
     BOOL(bool),
     NULL,
-    BLOCK(Vec<Expression>),
+    /// Block of code, like a body of a function or a loop.
+    BLOCK(Block),
     TYPE(Type),
+    /// Parameters of a function being defined. Only made by the parser.
     FUNCTION_ARGUMENTS(Vec<String>),
     KEY_VALUE(String, Box<Value>),
-    TABLE(HashMap<String, Value>),
-    ERROR(String),
-    ENVIRONMENT(Box<Environment>)
+    TABLE(Table),
+    ERROR(Rc<ErrorValue>),
+    ENVIRONMENT(Box<Environment>),
+    USER_DATA(Rc<dyn UserData>),
+    /// Pattern of a case of match. Only made by the parser.
+    PATTERN(PatternValue),
+    /// Instance of a record type.
+    RECORD(Rc<Record>)
 }
 
 impl Debug for Value {
@@ -41,17 +84,23 @@ impl Debug for Value {
             },
             Value::STRING(string) => write!(f, "{}", string),
             Value::NUMBER(number) => write!(f, "{}", number),
-            Value::FUNCTION(arguments, value) => write!(f, "Function {{ arguments: {:?}, value: {:?} }}", arguments, value),
+            Value::INTEGER(integer) => write!(f, "{}", integer),
+            Value::BIG_INTEGER(integer) => write!(f, "{}n", integer),
+            Value::DECIMAL(decimal) => write!(f, "{}d", decimal.to_plain_string()),
+            Value::FUNCTION(function) => write!(f, "{:?}", function),
             Value::NATIVE_FUNCTION(_, _) => write!(f, "Native function"),
             Value::BOOL(boolean) => write!(f, "{}", boolean),
             Value::NULL => write!(f, "null"),
-            Value::BLOCK(expressions) => write!(f, "Block {{ expressions: {:?} }}", expressions),
+            Value::BLOCK(block) => write!(f, "Block {{ expressions: {:?} }}", block),
             Value::TYPE(type_) => write!(f, "Type {{ type: {:?} }}", type_),
             Value::FUNCTION_ARGUMENTS(arguments) => write!(f, "Function arguments {{ arguments: {:?} }}", arguments),
             Value::KEY_VALUE(key, value) => write!(f, "Key value {{ key: {:?}, value: {:?} }}", key, value),
             Value::TABLE(table) => write!(f, "Table {{ table: {:?} }}", table),
-            Value::ERROR(error) => write!(f, "Error {{ error: {:?} }}", error),
+            Value::ERROR(error) => write!(f, "Error {{ kind: {:?}, message: {:?}, location: {:?} }}", error.kind, error.message, error.location),
             Value::ENVIRONMENT(_) => write!(f, "Environment"),
+            Value::USER_DATA(object) => write!(f, "User data {{ type: {} }}", object.type_name()),
+            Value::PATTERN(pattern) => write!(f, "Pattern {{ pattern: {:?} }}", pattern),
+            Value::RECORD(record) => write!(f, "Record {{ type: {}, values: {:?} }}", record.record_type.name, record.values),
         }
     }
 }
@@ -86,10 +135,35 @@ impl PartialEq<Self> for Value {
             return false;
         }
 
+        // Exact numbers are compared exactly, so large integers which have the same float aren't equal.
+        if self.is_number() && other.is_number() && !matches!((self, other), (Value::NUMBER(_), Value::NUMBER(_))) {
+            return number::compare(self, other) == Some(std::cmp::Ordering::Equal);
+        }
+
+        // Host objects are equal only to themselves.
+        if let Value::USER_DATA(object1) = self {
+            return if let Value::USER_DATA(object2) = other {
+                Rc::ptr_eq(object1, object2)
+            } else {
+                false
+            }
+        }
+
+        if let Value::USER_DATA(_) = other {
+            return false;
+        }
+
         if let Value::ENVIRONMENT(_) = other {
             return false;
         }
 
+        // Records of different types aren't equal, even if the types have the same name.
+        if let (Value::RECORD(record1), Value::RECORD(record2)) = (self, other) {
+            if record1.record_type != record2.record_type {
+                return false;
+            }
+        }
+
         self.text_representation() == other.text_representation()
     }
 }
@@ -103,10 +177,13 @@ impl Value {
         match self {
             Value::STRING(_) => Type::STRING,
             Value::NUMBER(_) => Type::NUMBER,
+            Value::INTEGER(_) => Type::INTEGER,
+            Value::BIG_INTEGER(_) => Type::BIG_INTEGER,
+            Value::DECIMAL(_) => Type::DECIMAL,
             Value::BOOL(_) => Type::BOOL,
             Value::NULL => Type::NULL,
             Value::LIST(_) => Type::LIST,
-            Value::FUNCTION(_, _) => Type::FUNCTION,
+            Value::FUNCTION(_) => Type::FUNCTION,
             Value::BLOCK(_) => Type::BLOCK,
             Value::TYPE(_) => Type::NULL,
             Value::FUNCTION_ARGUMENTS(_) => Type::FUNCTION_ARGUMENTS,
@@ -114,30 +191,93 @@ impl Value {
             Value::TABLE(_) => Type::TABLE,
             Value::ERROR(_) => Type::ERROR,
             Value::NATIVE_FUNCTION(_, _) => Type::FUNCTION,
-            Value::ENVIRONMENT(_) => Type::ENVIRONMENT
+            Value::ENVIRONMENT(_) => Type::ENVIRONMENT,
+            Value::USER_DATA(object) => Type::USER_DATA(object.type_name()),
+            Value::PATTERN(_) => Type::PATTERN,
+            Value::RECORD(record) => Type::RECORD(record.record_type.clone())
         }
     }
 
     pub fn text_representation(&self) -> String {
         match self {
-            Value::STRING(value) => value.clone(),
+            Value::STRING(value) => value.to_string(),
             Value::NUMBER(value) => value.to_string(),
+            Value::INTEGER(value) => value.to_string(),
+            Value::BIG_INTEGER(value) => value.to_string(),
+            Value::DECIMAL(value) => value.to_plain_string(),
             Value::BOOL(value) => if *value { "true".to_string() } else { "false".to_string() },
             Value::NULL => "NULL".to_string(),
             Value::LIST(values) => self.text_representation_of_list(values),
-            Value::FUNCTION(_, _) => "<FUNCTION>".to_string(),
+            Value::FUNCTION(_) => "<FUNCTION>".to_string(),
             Value::BLOCK(_) => "<BLOCK>".to_string(),
             Value::TYPE(a_type) => a_type.text_representation(),
             Value::FUNCTION_ARGUMENTS(_) => "<FUNCTION_ARGUMENTS>".to_string(),
             Value::KEY_VALUE(key, value) => format!("{}({})", key, value.text_representation()),
             Value::TABLE(_) => "<TABLE>".to_string(),
-            Value::ERROR(error) => error.to_string(),
+            Value::ERROR(error) => error.text_representation(),
             Value::NATIVE_FUNCTION(_, _) => "<FUNCTION>".to_string(),
-            Value::ENVIRONMENT(_) => "<ENVIRONMENT>".to_string()
+            Value::ENVIRONMENT(_) => "<ENVIRONMENT>".to_string(),
+            Value::USER_DATA(object) => format!("<{}>", object.type_name()),
+            Value::PATTERN(_) => "<PATTERN>".to_string(),
+            Value::RECORD(record) => record.text_representation()
+        }
+    }
+
+    /// Whether the value has the type. All kinds of numbers are Numbers and big integers are also Integers.
+    pub fn is_of_type(&self, a_type: &Type) -> bool {
+        let own_type = self.get_type();
+
+        own_type == *a_type || match a_type {
+            Type::NUMBER => self.is_number(),
+            Type::INTEGER => own_type == Type::BIG_INTEGER,
+            _ => false
+        }
+    }
+
+    pub fn is_number(&self) -> bool {
+        matches!(self, Value::NUMBER(_) | Value::INTEGER(_) | Value::BIG_INTEGER(_) | Value::DECIMAL(_))
+    }
+
+    /// Value of an integer which fits in 64 bits. Returns None for other values.
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            Value::INTEGER(integer) => Some(*integer),
+            Value::BIG_INTEGER(integer) => integer.to_i64(),
+            _ => None
         }
     }
 
-    fn text_representation_of_list(&self, values: &Vec<Value>) -> String {
+    /// Value of a number as a float, which can lose precision of large integers. Returns None for other values.
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            Value::NUMBER(number) => Some(*number),
+            Value::INTEGER(integer) => Some(*integer as f64),
+            Value::BIG_INTEGER(integer) => integer.to_f64(),
+            Value::DECIMAL(decimal) => decimal.to_f64(),
+            _ => None
+        }
+    }
+
+    /// Approximate number of bytes used by the value, including values it contains. Functions, modules and host objects are counted without what they refer to.
+    pub fn approximate_size(&self) -> usize {
+        let contents = match self {
+            Value::LIST(values) => values.iter().map(Value::approximate_size).sum(),
+            Value::STRING(string) => string.len(),
+            Value::BIG_INTEGER(integer) => integer.bits() as usize / 8,
+            Value::DECIMAL(decimal) => decimal.as_bigint_and_exponent().0.bits() as usize / 8,
+            Value::KEY_VALUE(key, value) => key.len() + value.approximate_size(),
+            Value::TABLE(table) => table.iter().map(|(key, value)| key.len() + value.approximate_size()).sum(),
+            Value::FUNCTION_ARGUMENTS(arguments) => arguments.iter().map(String::len).sum(),
+            Value::FUNCTION(function) => function.parameters.iter().map(String::len).sum(),
+            Value::ERROR(error) => error.message.len() + error.kind.len(),
+            Value::RECORD(record) => record.values.iter().map(Value::approximate_size).sum(),
+            _ => 0
+        };
+
+        std::mem::size_of::<Value>() + contents
+    }
+
+    fn text_representation_of_list(&self, values: &List) -> String {
         let mut final_string = String::new();
 
         for value in values {
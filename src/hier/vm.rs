@@ -0,0 +1,82 @@
+use crate::hier::compiler::{Chunk, Instruction};
+use crate::hier::environment::Environment;
+use crate::hier::hier_error::HierError;
+use crate::hier::value::Value;
+
+impl Environment {
+    /// Executes a compiled block on a value stack and returns the value of its last expression.
+    pub fn execute(&mut self, chunk: &Chunk) -> Result<Value, HierError> {
+        let mut stack: Vec<Value> = Vec::new();
+        let mut ip = 0;
+
+        while ip < chunk.instructions.len() {
+            if let Some(location) = &chunk.locations[ip] {
+                self.current_interpreting_location = location.clone();
+            }
+
+            self.step()?;
+
+            match &chunk.instructions[ip] {
+                Instruction::CONSTANT(constant) => stack.push(chunk.constants[*constant].clone()),
+                Instruction::STRING(string) => {
+                    let string = chunk.strings[*string].resolve(self)?;
+                    stack.push(Value::STRING(string.into()));
+                },
+                Instruction::GET(name) => {
+                    let value = self.get(chunk.names[*name].clone())?;
+                    stack.push(value);
+                },
+                Instruction::KEY_VALUE(key) => {
+                    let value = stack.pop().unwrap_or(Value::NULL);
+                    stack.push(Value::KEY_VALUE(chunk.names[*key].clone(), Box::new(value)));
+                },
+                Instruction::LIST(count) => {
+                    let values = stack.split_off(stack.len() - count);
+                    stack.push(self.check_size(Value::LIST(values.into()))?);
+                },
+                Instruction::CALL_BUILTIN(builtin, count) => {
+                    let arguments = stack.split_off(stack.len() - count);
+                    let value = self.call_builtin(builtin, arguments)?;
+                    stack.push(value);
+                },
+                Instruction::CALL(name, count) => {
+                    let arguments = stack.split_off(stack.len() - count);
+                    let value = self.call_defined_function(&chunk.names[*name], arguments)?;
+                    stack.push(value);
+                },
+                Instruction::CALL_METHOD(name, count) => {
+                    let arguments = stack.split_off(stack.len() - count);
+                    let value = self.call_method(&chunk.names[*name], arguments)?;
+                    stack.push(value);
+                },
+                Instruction::ANONYMOUS_FUNCTION(function, end) => {
+                    if let Some(Value::FUNCTION_ARGUMENTS(arguments)) = stack.pop() {
+                        match function {
+                            Ok(block) => {
+                                let Value::BLOCK(block) = &chunk.constants[*block] else {
+                                    return self.error("Anonymous function's second argument must be a block.");
+                                };
+
+                                stack.push(self.make_function(arguments, block.clone()));
+                                ip = *end;
+                                continue;
+                            },
+                            Err(message) => return self.error(&chunk.names[*message])
+                        }
+                    }
+                },
+                Instruction::POP => { stack.pop(); },
+                Instruction::ERROR(message) => return self.error(&chunk.names[*message])
+            }
+
+            // Something like a return is unwinding, so the rest of the block is skipped.
+            if self.control_flow.is_some() {
+                return Ok(Value::NULL);
+            }
+
+            ip += 1;
+        }
+
+        Ok(stack.pop().unwrap_or(Value::NULL))
+    }
+}
@@ -0,0 +1,143 @@
+//! Hier is a Lisp-like toy programming language. This crate contains its interpreter, which can be embedded in Rust programs using [Hier].
+//!
+//! Rust functions with arguments and results implementing [FromValue] and [IntoValue] can be added directly. Number and types of arguments are checked automatically:
+//!
+//! ```
+//! use hier::{Hier, Policy};
+//!
+//! fn copies(text: String, count: i64) -> Result<String, String> {
+//!     if count < 0 {
+//!         return Err("Count can't be negative.".to_string());
+//!     }
+//!
+//!     Ok(text.repeat(count as usize))
+//! }
+//!
+//! let mut hier = Hier::new("./main".to_string(), |path| std::fs::read_to_string(path).map_err(|error| error.to_string()), || std::process::exit(0), false, Policy::default());
+//! hier.add_function("copies".to_string(), copies);
+//! hier.add_function("sum".to_string(), |numbers: Vec<f64>| numbers.iter().sum::<f64>());
+//!
+//! assert_eq!(hier.run("(copies \"ab\" 2)".to_string()).unwrap().text_representation(), "abab");
+//! assert_eq!(hier.run("(sum (& 1 2 3))".to_string()).unwrap().text_representation(), "6");
+//! assert!(hier.run("(copies 1 2)".to_string()).is_err());
+//! ```
+//!
+//! Functions which need the environment or take any number of arguments can get them as values. They can also be closures, which capture state of the host:
+//!
+//! ```
+//! use hier::{Hier, Policy, Value};
+//!
+//! let mut hier = Hier::new("./main".to_string(), |path| std::fs::read_to_string(path).map_err(|error| error.to_string()), || std::process::exit(0), false, Policy::default());
+//! let mut count = 0.0;
+//!
+//! hier.add_native_function_mut("count".to_string(), -1, move |_environment, arguments| {
+//!     count += arguments.len() as f64;
+//!     Ok(Value::NUMBER(count))
+//! });
+//!
+//! assert_eq!(hier.run("(count 1 2) (count 3)".to_string()).unwrap().text_representation(), "3");
+//! ```
+//!
+//! Globals declared by the code can be read and functions can be called from Rust:
+//!
+//! ```
+//! use hier::{Hier, IntoValue, Policy};
+//!
+//! let mut hier = Hier::new("./main".to_string(), |path| std::fs::read_to_string(path).map_err(|error| error.to_string()), || std::process::exit(0), false, Policy::default());
+//! hier.run("(@clicks 0) (@on_click (| x y) { (=clicks (+ clicks 1)) (+ x y) })".to_string()).unwrap();
+//!
+//! assert_eq!(hier.call_global("on_click", vec![1.0.into_value(), 2.0.into_value()]).unwrap().text_representation(), "3");
+//! assert_eq!(hier.get_global("clicks").unwrap().text_representation(), "1");
+//! ```
+//!
+//! Objects of the host can be passed to Hier as [UserData]. They can have properties and methods:
+//!
+//! ```
+//! use std::cell::Cell;
+//! use std::rc::Rc;
+//! use hier::{Environment, Hier, HierError, Policy, UserData, Value};
+//!
+//! struct Counter {
+//!     count: Cell<f64>
+//! }
+//!
+//! impl UserData for Counter {
+//!     fn type_name(&self) -> String {
+//!         "Counter".to_string()
+//!     }
+//!
+//!     fn get_property(&self, name: &str) -> Option<Value> {
+//!         if name == "count" { Some(Value::NUMBER(self.count.get())) } else { None }
+//!     }
+//!
+//!     fn has_method(&self, name: &str) -> bool {
+//!         name == "add"
+//!     }
+//!
+//!     fn call_method(&self, environment: &mut Environment, _name: &str, arguments: Vec<Value>) -> Result<Value, HierError> {
+//!         match arguments.first().and_then(Value::as_number) {
+//!             Some(number) => {
+//!                 self.count.set(self.count.get() + number);
+//!                 Ok(Value::NULL)
+//!             },
+//!             None => environment.error("Add requires a number.")
+//!         }
+//!     }
+//! }
+//!
+//! let mut hier = Hier::new("./main".to_string(), |path| std::fs::read_to_string(path).map_err(|error| error.to_string()), || std::process::exit(0), false, Policy::default());
+//! hier.add_variable("counter".to_string(), Value::USER_DATA(Rc::new(Counter { count: Cell::new(0.0) })));
+//!
+//! assert_eq!(hier.run("(counter.add 2) (counter.add 3) counter.count".to_string()).unwrap().text_representation(), "5");
+//! assert_eq!(hier.run("(is counter \"Counter\")".to_string()).unwrap().text_representation(), "true");
+//! ```
+//!
+//! Code can only do what its [Policy] allows. By default it can't read or write files, run processes, import modules outside of the directory of the main module or use eval. Denied operations produce errors of kind PermissionError:
+//!
+//! ```
+//! use hier::{Hier, Policy};
+//!
+//! let mut hier = Hier::new("./main".to_string(), |path| std::fs::read_to_string(path).map_err(|error| error.to_string()), || std::process::exit(0), false, Policy::default());
+//! assert_eq!(hier.run("(try (eval \"(+ 1 2)\") { error.kind })".to_string()).unwrap().text_representation(), "PermissionError");
+//!
+//! let mut hier = Hier::new("./main".to_string(), |path| std::fs::read_to_string(path).map_err(|error| error.to_string()), || std::process::exit(0), false, Policy { eval: true, ..Policy::default() });
+//! assert_eq!(hier.run("(eval \"(+ 1 2)\")".to_string()).unwrap().text_representation(), "3");
+//! ```
+//!
+//! Resources used by every run can be limited using [Limits]. Code which exceeds them is stopped with an error of kind [HierErrorKind::LIMIT]:
+//!
+//! ```
+//! use hier::{Hier, HierErrorKind, Limit, Limits, Policy};
+//!
+//! let mut hier = Hier::new("./main".to_string(), |path| std::fs::read_to_string(path).map_err(|error| error.to_string()), || std::process::exit(0), false, Policy::default());
+//! hier.set_limits(Limits { steps: Some(10_000), ..Limits::default() });
+//!
+//! let error = hier.run("(repeat { (+ 1 2) })".to_string()).unwrap_err();
+//! assert_eq!(error.kind, HierErrorKind::LIMIT(Limit::STEPS));
+//! ```
+//!
+//! Running code can also be stopped from another thread using a [CancelHandle], returned by [Hier::cancel_handle].
+
+mod hier;
+
+pub use bigdecimal::BigDecimal;
+pub use num_bigint::BigInt;
+
+pub use crate::hier::backend::Backend;
+pub use crate::hier::block::Block;
+pub use crate::hier::call::Call;
+pub use crate::hier::cancellation::CancelHandle;
+pub use crate::hier::conversion::{FromValue, IntoNativeFunction, IntoResult, IntoValue};
+pub use crate::hier::environment::{Environment, ExitHandler, ModuleReader};
+pub use crate::hier::error_value::ErrorValue;
+pub use crate::hier::function::Function;
+pub use crate::hier::hier::Hier;
+pub use crate::hier::hier_error::{HierError, HierErrorKind};
+pub use crate::hier::limits::{Limit, Limits};
+pub use crate::hier::location::Location;
+pub use crate::hier::pattern::PatternValue;
+pub use crate::hier::policy::Policy;
+pub use crate::hier::record::{Record, RecordType};
+pub use crate::hier::types::Type;
+pub use crate::hier::user_data::UserData;
+pub use crate::hier::value::{List, NativeFunction, Table, Value};
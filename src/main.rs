@@ -1,94 +1,146 @@
 mod functions;
-mod hier;
 
 extern crate core;
 
-use std::{env, fs, io, panic};
+use std::{env, fs, io};
 use std::env::current_dir;
 use std::io::Write;
-use std::path::PathBuf;
 use std::process::exit;
-use hier::environment::{Environment, VariableId};
-use hier::expression::Expression;
-use hier::hier::Hier;
-use hier::parser::Parser;
-use hier::tokenizer::Tokenizer;
-use hier::value::Value;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::path::PathBuf;
+use std::time::Duration;
+use hier::{Backend, Hier, Limits, Policy, Value};
 use functions::*;
 
 fn print_usage() {
-    eprintln!("Usage: hier <command>");
+    eprintln!("Usage: hier [options] <command>");
     eprintln!("Commands:");
     eprintln!(" repl - Runs REPL. Can be omitted by running without arguments.");
     eprintln!(" file <path> - Runs contents of file. Can be omitted by running with only path.");
     eprintln!(" run <a string of code>  - Runs a string.");
+    eprintln!("Options:");
+    eprintln!(" --backend <vm|ast> - Selects how code is executed: compiled to bytecode (vm, default) or by walking the syntax tree (ast).");
+    eprintln!(" --allow-read <directory> - Allows reading files in a directory. Can be used multiple times.");
+    eprintln!(" --allow-write <directory> - Allows writing files in a directory. Can be used multiple times.");
+    eprintln!(" --allow-process - Allows running processes using cmd.");
+    eprintln!(" --allow-imports - Allows importing modules outside of the directory of the main module.");
+    eprintln!(" --allow-eval - Allows using eval.");
+    eprintln!(" --allow-all - Allows everything.");
+    eprintln!(" --max-steps <count> - Stops the code after evaluating given number of expressions.");
+    eprintln!(" --timeout <seconds> - Stops the code after given time.");
+    eprintln!(" --max-depth <count> - Limits nested calls of functions (10000 by default).");
+    eprintln!(" --max-value-size <bytes> - Limits approximate size of a single value (not the total memory used).");
+}
+
+struct Options {
+    backend: Backend,
+    policy: Policy,
+    limits: Limits
+}
+
+/// Removes options from arguments and returns the remaining arguments with parsed options.
+fn parse_options(args: Vec<String>) -> (Vec<String>, Options) {
+    let mut options = Options {
+        backend: Backend::VM,
+        policy: Policy::default(),
+        limits: Limits::default()
+    };
+
+    let mut remaining: Vec<String> = vec![];
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        match &arg as &str {
+            "--backend" => {
+                options.backend = match args.next().and_then(|name| Backend::get_for_name(&name)) {
+                    Some(backend) => backend,
+                    None => { print_usage(); exit(1) }
+                };
+            },
+            "--allow-read" | "--allow-write" => {
+                let directory = match args.next() {
+                    Some(directory) => PathBuf::from(directory),
+                    None => { print_usage(); exit(1) }
+                };
+
+                if arg == "--allow-read" {
+                    options.policy.read.push(directory);
+                } else {
+                    options.policy.write.push(directory);
+                }
+            },
+            "--allow-process" => options.policy.process = true,
+            "--allow-imports" => options.policy.imports_outside_root = true,
+            "--allow-eval" => options.policy.eval = true,
+            "--allow-all" => options.policy = Policy::allow_all(),
+            "--max-steps" => options.limits.steps = Some(parse_number(args.next())),
+            "--timeout" => options.limits.time = match args.next().and_then(|seconds| Duration::try_from_secs_f64(seconds.parse().ok()?).ok()) {
+                Some(time) => Some(time),
+                None => { print_usage(); exit(1) }
+            },
+            "--max-depth" => options.limits.depth = Some(parse_number(args.next())),
+            "--max-value-size" => options.limits.value_size = Some(parse_number(args.next())),
+            _ => remaining.push(arg)
+        }
+    }
+
+    (remaining, options)
+}
+
+/// Parses a value of an option, which must be a whole number.
+fn parse_number<T: std::str::FromStr>(value: Option<String>) -> T {
+    match value.and_then(|value| value.parse().ok()) {
+        Some(number) => number,
+        None => { print_usage(); exit(1) }
+    }
 }
 
-fn module_reader(path: String) -> String {
-    fs::read_to_string(path.clone())
-        .expect(&format!("Unable to read the file: {}", path))
+fn module_reader(path: String) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|error| error.to_string())
 }
 
-fn exit_handler() -> ! {
+fn exit_handler() {
     exit(0)
 }
 
 fn add_defaults(hier: &mut Hier) {
     hier.add_variable("cwd".to_string(), match current_dir() {
-        Ok(path) => Value::STRING(path.to_str().unwrap().to_string()),
+        Ok(path) => Value::STRING(path.to_string_lossy().into()),
         Err(_) => Value::NULL,
     });
 
     let mut args: Vec<String> = env::args().collect();
     args.remove(0);
-    hier.add_variable("args".to_string(), Value::LIST(args.iter().map(|arg| Value::STRING(arg.to_string())).collect()));
+    hier.add_variable("args".to_string(), Value::LIST(args.iter().map(|arg| Value::STRING(arg.as_str().into())).collect()));
 
-    hier.add_function("time".to_string(), 0, time_function);
-    hier.add_function("rand".to_string(), 2, rand_function);
-    hier.add_function("cmd".to_string(), -1, cmd_function);
-    hier.add_function("write".to_string(), 2, write_function);
-    hier.add_function("file".to_string(), 1, file_function);
+    hier.add_function("time".to_string(), time_function);
+    hier.add_function("rand".to_string(), rand_function);
+    hier.add_native_function("cmd".to_string(), -1, cmd_function);
+    hier.add_function("write".to_string(), write_function);
+    hier.add_function("file".to_string(), file_function);
 }
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let (args, options) = parse_options(env::args().collect());
 
     if args.len() == 1 {
-        let mut hier = Hier::new("./repl".to_string(), module_reader, exit_handler, false);
-        add_defaults(&mut hier);
-        repl();
+        repl(options);
     } else if args.len() == 2 {
-        print_usage();
-        exit(1); 
+        match &args[1] as &str {
+            "repl" => repl(options),
+            path => run_file(path, options, false)
+        }
     } else if args.len() == 3 {
         match &args[1] as &str {
-            "file" => {
-                let path = args[2].clone();
-                let contents = fs::read_to_string(path.clone())
-                    .expect("Unable to read the file.");
-                let full_path = fs::canonicalize(PathBuf::from(path)).expect("Unable to resolve file.").to_str().unwrap().to_string();
-                let mut hier = Hier::new(full_path, module_reader, exit_handler, false);
-                add_defaults(&mut hier);
-                hier.run(contents);
-            },
-            "debug" => {
-                let path = args[2].clone();
-                let contents = fs::read_to_string(path.clone())
-                    .expect("Unable to read the file.");
-                let full_path = fs::canonicalize(PathBuf::from(path)).expect("Unable to resolve file.").to_str().unwrap().to_string();
-                let mut hier = Hier::new(full_path, module_reader, exit_handler, true);
-                add_defaults(&mut hier);
-                hier.run(contents);
-            },
+            "file" => run_file(&args[2], options, false),
+            "debug" => run_file(&args[2], options, true),
             "run" => {
-                let mut hier = Hier::new("./code".to_string(), module_reader, exit_handler, false);
-                add_defaults(&mut hier);
-                hier.run(args[2].clone());
-            },
-            "repl" => {
-                let mut hier = Hier::new("./repl".to_string(), module_reader, exit_handler, false);
+                let mut hier = Hier::new("./code".to_string(), module_reader, exit_handler, false, options.policy);
                 add_defaults(&mut hier);
-                repl()
+                hier.set_backend(options.backend);
+                hier.set_limits(options.limits);
+                report(hier.run(args[2].clone()));
             },
             _ => { print_usage(); exit(1) }
         }
@@ -98,25 +150,51 @@ fn main() {
     }
 }
 
-fn repl() -> ! {
-    println!("Hier REPL");
-    println!("Type (exit) or exit to exit."); // :)
-    let mut repl_environment = Environment::new(true, "./repl".to_string(), module_reader, exit_handler, false, vec![]);
-
-    repl_environment.values.insert(VariableId(0, "cwd".to_string()), match current_dir() {
-        Ok(path) => Value::STRING(path.to_str().unwrap().to_string()),
-        Err(_) => Value::NULL,
-    });
+fn run_file(path: &str, options: Options, debug: bool) {
+    let mut hier = Hier::new(path.to_string(), module_reader, exit_handler, debug, options.policy);
+    add_defaults(&mut hier);
+    hier.set_backend(options.backend);
+    hier.set_limits(options.limits);
+    report(hier.run_file(path));
+}
 
-    let mut args: Vec<String> = env::args().collect();
-    args.remove(0);
-    repl_environment.values.insert(VariableId(0, "args".to_string()), Value::LIST(args.iter().map(|arg| Value::STRING(arg.to_string())).collect()));
+/// Exits with an error status if the code failed.
+fn report(result: Result<Value, hier::HierError>) {
+    if let Err(error) = result {
+        eprintln!("{}", error);
+        exit(1);
+    }
+}
 
-    repl_environment.values.insert(VariableId(0, "time".to_string()), Value::NATIVE_FUNCTION(time_function, 0));
-    repl_environment.values.insert(VariableId(0, "rand".to_string()), Value::NATIVE_FUNCTION(rand_function, 2));
-    repl_environment.values.insert(VariableId(0, "cmd".to_string()), Value::NATIVE_FUNCTION(cmd_function, 1));
-    repl_environment.values.insert(VariableId(0, "write".to_string()), Value::NATIVE_FUNCTION(write_function, 2));
-    repl_environment.values.insert(VariableId(0, "file".to_string()), Value::NATIVE_FUNCTION(file_function, 1));
+fn repl(options: Options) -> ! {
+    println!("Hier REPL");
+    println!("Type (exit) or exit to exit."); // :)
+    let mut hier = Hier::new_repl(module_reader, exit_handler, options.policy);
+    add_defaults(&mut hier);
+    hier.set_backend(options.backend);
+    hier.set_limits(options.limits);
+
+    // Ctrl-C stops the running line instead of the REPL, so variables aren't lost.
+    // At the prompt the first Ctrl-C shows a hint and the second one exits.
+    let cancel_handle = hier.cancel_handle();
+    let running = Arc::new(AtomicBool::new(false));
+    let exit_pending = Arc::new(AtomicBool::new(false));
+    let handler_running = running.clone();
+    let handler_exit_pending = exit_pending.clone();
+    let handler = move || {
+        if handler_running.load(Ordering::SeqCst) {
+            cancel_handle.cancel();
+        } else if handler_exit_pending.swap(true, Ordering::SeqCst) {
+            println!();
+            exit_handler();
+        } else {
+            print!("\n(Press Ctrl-C again or type exit to exit.)\n> ");
+            std::io::stdout().flush().expect("Failed to flush stdout.");
+        }
+    };
+    if let Err(error) = ctrlc::set_handler(handler) {
+        eprintln!("Failed to handle Ctrl-C: {}.", error);
+    }
 
     loop {
         print!("> ");
@@ -128,51 +206,18 @@ fn repl() -> ! {
             exit_handler();
         };
 
-        if line == "(exit)\n" || line == "exit\n" {
+        // Empty line without a new line means end of input.
+        if line.is_empty() || line == "(exit)\n" || line == "exit\n" {
             exit_handler();
         }
 
-        let mut tokenizer = Tokenizer::new(line);
-
-        tokenizer.module_name = "REPL".to_string();
-
-        if tokenizer.tokenize_code() {
-            eprintln!("Failed.");
-            continue;
-        }
-
-        let mut parser = Parser::new(tokenizer.tokens, module_reader, exit_handler);
-
-        if parser.parse() {
-            eprintln!("Failed.");
-            continue;
-        }
-
-        let code = if let Expression::BLOCK(code, _) = parser.code {
-            code
-        } else {
-            vec![parser.code]
-        };
-
-        let environment = repl_environment.clone();
-
-        let current_hook = panic::take_hook();
-
-        panic::set_hook(Box::new(|_info| {
-            // Do nothing.
-        }));
-
-        let value = panic::catch_unwind(move || {
-            let mut environment = environment.clone();
-            let value = environment.interpret_block(code);
-            (value, environment.values)
-        });
-
-        panic::set_hook(current_hook);
-
-        match value {
-            Ok((value, values)) => { println!("{}", value.text_representation()); repl_environment.values = values },
-            _ => { }
+        exit_pending.store(false, Ordering::SeqCst);
+        running.store(true, Ordering::SeqCst);
+        let result = hier.run_line(line);
+        running.store(false, Ordering::SeqCst);
+        match result {
+            Ok(value) => println!("{}", value.text_representation()),
+            Err(error) => eprintln!("{}", error)
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,13 @@
+//! Runs the same code on both backends and checks that they return the same values and errors (including locations and backtraces).
+
+mod common;
+
+use common::*;
+
+#[test]
+fn loops() {
+    assert_eq!(value("(@sum 0) (for (& 1 2 3 4) { (=sum (+ sum element)) }) sum"), "10");
+    assert_eq!(value("(@i 0) (while { (< i 5) } { (=i (+ i 1)) }) i"), "5");
+    assert_eq!(value("(@n 0) (repeat 3 { (=n (+ n 2)) }) n"), "6");
+    assert_eq!(value("(map (& 1 2 3) { (* element 2) })"), "(2 4 6)");
+}
@@ -0,0 +1,23 @@
+//! Fatal errors show calls which led to them, with modules which defined the called functions.
+
+mod common;
+
+use common::*;
+
+#[test]
+fn fatal_errors_have_backtraces() {
+    assert_eq!(error("(@f (| a) { (+ a null) })\n(@g (| a) { (f a) })\n(println 1 (g 1))"), "(1:13 in main) !: Argument must be a number or string in addition. Found NULL.\nBacktrace:\n  + (native) called at 1:13 in main\n  f (./main) called at 2:13 in main\n  g (./main) called at 3:12 in main");
+    assert!(error("(println \"x\" (while { 5 } { 1 }))").starts_with("(1:14 in main)"));
+    assert!(error("(println \"x\" (keys (+ 1 2)))").starts_with("(1:14 in main)"));
+}
+
+#[test]
+fn backtraces_show_modules_which_defined_functions() {
+    for backend in BACKENDS {
+        let mut hier = hier_with_module(backend, "lib", "(@secret 1) (@fail (|) { (+ secret null) })");
+        let error = hier.run("(@lib (import \"lib\")) (@h lib::fail) (h)".to_string()).unwrap_err().to_string();
+
+        assert!(error.contains("\n  h ("), "{}", error);
+        assert!(error.contains("lib.hier) called at 1:38 in main"), "{}", error);
+    }
+}
@@ -0,0 +1,13 @@
+//! Integers of any size and exact decimals.
+
+mod common;
+
+use common::*;
+
+#[test]
+fn big_integers_and_decimals() {
+    assert_eq!(value("(* 9223372036854775807n 2)"), "18446744073709551614");
+    assert!(error("(* 9223372036854775807 2)").contains("Integer overflow"));
+    assert_eq!(value("(& (+ 0.10d 0.20d) (== 0.3d (+ 0.1d 0.2d)) (round 2.5d) (is 1n BigInteger))"), "(0.30 true 3 true)");
+    assert!(error("(+ 1.5d 1.5)").contains("can't be used with float"));
+}
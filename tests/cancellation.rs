@@ -0,0 +1,29 @@
+//! Running code can be cancelled from another thread, and the host keeps its state.
+
+mod common;
+
+use std::thread;
+use std::time::Duration;
+use hier::{HierErrorKind, Limits, Policy};
+use common::*;
+
+#[test]
+fn cancellation() {
+    for backend in BACKENDS {
+        let mut hier = hier(backend, Policy::default());
+        let handle = hier.cancel_handle();
+
+        // Time limit only stops the loop if cancelling didn't work.
+        hier.set_limits(Limits { time: Some(Duration::from_secs(10)), ..Limits::default() });
+        hier.run("(@x 1)".to_string()).unwrap();
+
+        let canceller = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            handle.cancel();
+        });
+
+        assert_eq!(hier.run("(repeat { 1 })".to_string()).unwrap_err().kind, HierErrorKind::CANCELLED);
+        canceller.join().unwrap();
+        assert_eq!(hier.run("(+ x 0)".to_string()).unwrap().text_representation(), "1");
+    }
+}
@@ -0,0 +1,23 @@
+//! Functions are closures, which see variables of the scope and the module in which they were defined.
+
+mod common;
+
+use common::*;
+
+#[test]
+fn closures() {
+    assert_eq!(value("(@make_counter (|) { (@count 0) ((|) { (=count (+ count 1)) count }) }) (@counter (make_counter)) (@other (make_counter)) (counter) (counter) (& (counter) (other))"), "(3 1)");
+    assert_eq!(value("(@adder (| n) { ((| x) { (+ x n) }) }) (@add5 (adder 5)) (add5 10)"), "15");
+}
+
+const LIB: &str = "(@secret 42) (@counter 0) (@get_secret (|) { secret }) (@bump (|) { (=counter (+ counter 1)) counter }) (@make (|) { ((| x) { (+ x secret) }) })";
+
+#[test]
+fn functions_use_globals_of_their_module() {
+    for backend in BACKENDS {
+        let mut hier = hier_with_module(backend, "lib", LIB);
+        let result = hier.run("(@lib (import \"lib\")) (@secret 1) (@f lib::get_secret) (@g (lib::make)) (lib::bump) (& (f) (g 1) (lib::bump) lib::counter secret)".to_string());
+
+        assert_eq!(describe(result), Ok("(42 43 2 2 1)".to_string()));
+    }
+}
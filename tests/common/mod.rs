@@ -0,0 +1,53 @@
+//! Helpers for tests, which run the same code on both backends and check that they return the same values and errors (including locations and backtraces).
+
+#![allow(dead_code)]
+
+use hier::{Backend, Hier, HierError, Policy, Value};
+
+pub const BACKENDS: [Backend; 2] = [Backend::AST, Backend::VM];
+
+pub fn hier(backend: Backend, policy: Policy) -> Hier {
+    let mut hier = Hier::new("./main".to_string(), |path| std::fs::read_to_string(path).map_err(|error| error.to_string()), || {}, false, policy);
+    hier.set_backend(backend);
+    hier
+}
+
+/// Text of the value with nested lists in parentheses, which text_representation flattens.
+pub fn show(value: &Value) -> String {
+    match value {
+        Value::LIST(list) => format!("({})", list.iter().map(show).collect::<Vec<String>>().join(" ")),
+        value => value.text_representation()
+    }
+}
+
+/// Value as text or the error as printed by the CLI, so errors are compared with their locations and backtraces.
+pub fn describe(result: Result<Value, HierError>) -> Result<String, String> {
+    result.map(|value| show(&value)).map_err(|error| error.to_string())
+}
+
+pub fn run_with(policy: Policy, code: &str) -> Result<String, String> {
+    let ast = describe(hier(Backend::AST, policy.clone()).run(code.to_string()));
+    let vm = describe(hier(Backend::VM, policy).run(code.to_string()));
+    assert_eq!(ast, vm, "Backends differ for {}", code);
+    ast
+}
+
+pub fn run(code: &str) -> Result<String, String> {
+    run_with(Policy::default(), code)
+}
+
+pub fn value(code: &str) -> String {
+    run(code).unwrap_or_else(|error| panic!("{} failed: {}", code, error))
+}
+
+pub fn error(code: &str) -> String {
+    run(code).expect_err(code)
+}
+
+/// Hier which can import one module, given as code instead of a file.
+pub fn hier_with_module(backend: Backend, name: &'static str, code: &'static str) -> Hier {
+    let file = format!("/{}.hier", name);
+    let mut hier = Hier::new("./main".to_string(), move |requested: String| if requested.ends_with(&file) { Ok(code.to_string()) } else { Err(format!("{} doesn't exist.", requested)) }, || {}, false, Policy::default());
+    hier.set_backend(backend);
+    hier
+}
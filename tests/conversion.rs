@@ -0,0 +1,17 @@
+//! Rust functions with typed arguments and results, which are converted and checked automatically.
+
+mod common;
+
+use hier::Policy;
+use common::*;
+
+#[test]
+fn typed_native_functions() {
+    for backend in BACKENDS {
+        let mut hier = hier(backend, Policy::default());
+        hier.add_function("copies".to_string(), |text: String, count: i64| text.repeat(count as usize));
+
+        assert_eq!(hier.run("(copies \"ab\" 2)".to_string()).unwrap().text_representation(), "abab");
+        assert_eq!(hier.run("(copies 1 2)".to_string()).unwrap_err().message, "Argument 1 of copies must be String, but 1 of type Integer was found.");
+    }
+}
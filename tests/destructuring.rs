@@ -0,0 +1,13 @@
+//! Declarations, assignments and parameters can destructure values with patterns.
+
+mod common;
+
+use common::*;
+
+#[test]
+fn destructuring() {
+    assert_eq!(value("(@(a b &rest) (& 1 2 3 4)) (& a b rest)"), "(1 2 (3 4))");
+    assert_eq!(value("(@config (table host:\"localhost\" port:80)) (@(# host port) config) (& host port)"), "(localhost 80)");
+    assert_eq!(value("(@a 1) (@b 2) (=(a b) (& b a)) (& a b)"), "(2 1)");
+    assert_eq!(value("(@dist (| (x y) (# scale)) { (* scale (+ x y)) }) (dist (& 1 2) (table scale:10))"), "30");
+}
@@ -0,0 +1,22 @@
+//! Values made by the code can be inspected by the host and passed back to it.
+
+mod common;
+
+use hier::{Policy, Value};
+use common::*;
+
+#[test]
+fn values_of_the_code_can_be_passed_back() {
+    for backend in BACKENDS {
+        let mut hier = hier(backend, Policy::default());
+        let double = hier.run("(@double (| x) { (* x 2) }) double".to_string()).unwrap();
+
+        assert!(matches!(double, Value::FUNCTION(_)));
+        assert_eq!(hier.call(&double, vec![Value::INTEGER(4)]).unwrap().text_representation(), "8");
+
+        match hier.run("(error \"Bad.\" kind:\"ConfigError\")".to_string()).unwrap() {
+            Value::ERROR(error) => assert_eq!((error.kind.as_str(), error.message.as_str()), ("ConfigError", "Bad.")),
+            value => panic!("Expected an error, found {}.", value.text_representation())
+        }
+    }
+}
@@ -0,0 +1,12 @@
+//! Errors which can be handled by the code, with their kinds, locations, traces, payloads and causes.
+
+mod common;
+
+use common::*;
+
+#[test]
+fn error_values() {
+    assert_eq!(value("(try (error \"Bad.\" kind:\"ConfigError\" payload:5 cause:(error \"Inner.\")) { (& error.kind error.message error.payload error.cause.message) })"), "(ConfigError Bad. 5 Inner.)");
+    assert_eq!(value("(@f (|) { (error \"In f.\") }) (try (f) { (& error.location (length error.trace)) })"), "(1:11 in main 1)");
+    assert_eq!(value("(try 5 { 6 })"), "5");
+}
@@ -0,0 +1,20 @@
+//! Runtime errors are returned to the host instead of panicking or exiting.
+
+mod common;
+
+use common::*;
+
+#[test]
+fn errors_are_returned() {
+    assert!(error("(+ 1").contains("(1:"));
+    assert!(error("(keys 1)").contains("Argument 1 of keys must be Table"));
+    assert!(error("(panic \"boom\")").contains("Panic: boom"));
+}
+
+#[test]
+fn arithmetic_without_arguments_is_an_error() {
+    assert!(error("(+)").contains("Addition requires at least 1 argument."));
+    assert!(error("(-)").contains("At least 1 argument is required in subtraction."));
+    assert!(error("(*)").contains("At least 1 argument is required in multiplication."));
+    assert!(error("(/)").contains("At least 1 argument is required in division."));
+}
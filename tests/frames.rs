@@ -0,0 +1,12 @@
+//! Every call has its own frame of variables, which is not visible to functions it calls.
+
+mod common;
+
+use common::*;
+
+#[test]
+fn frames() {
+    assert_eq!(value("(@x 1) (@f (|) { (@x 2) x }) (& (f) x)"), "(2 1)");
+    assert_eq!(value("(@leak (|) { local }) (@caller (|) { (@local 1) (leak) }) (caller)"), "NULL");
+    assert_eq!(value("(@fact (| n) { (if (< n 2) { 1 } { (* n (fact (- n 1))) }) }) (fact 5)"), "120");
+}
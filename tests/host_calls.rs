@@ -0,0 +1,32 @@
+//! The host can read globals of the code and call its functions.
+
+mod common;
+
+use hier::{IntoValue, Policy};
+use common::*;
+
+#[test]
+fn host_can_read_globals_and_call_functions() {
+    for backend in BACKENDS {
+        let mut hier = hier(backend, Policy::default());
+        hier.run("(@clicks 0) (@on_click (| x y) { (=clicks (+ clicks 1)) (+ x y) })".to_string()).unwrap();
+
+        assert_eq!(hier.call_global("on_click", vec![1.into_value(), 2.into_value()]).unwrap().text_representation(), "3");
+        assert_eq!(hier.get_global("clicks").unwrap().text_representation(), "1");
+    }
+}
+
+#[test]
+fn failed_calls_restore_the_state() {
+    for backend in BACKENDS {
+        let mut hier = hier(backend, Policy::default());
+        hier.run("(@f (| x) { (@local 1) (+ x null) }) (@h (| n) { (if (== n 0) { (+ 1 null) } { (h (- n 1)) }) })".to_string()).unwrap();
+
+        assert!(hier.call_global("f", vec![1.into_value()]).is_err());
+        assert!(hier.get_global("local").is_none());
+        assert_eq!(describe(hier.run("(@y 5) (@g (| x) { (@local 2) x }) (& (g y) local)".to_string())), Ok("(5 NULL)".to_string()));
+        assert_eq!(hier.run("(h 3)".to_string()).unwrap_err().trace.len(), 9);
+        assert_eq!(hier.run("(h 0)".to_string()).unwrap_err().trace.len(), 3);
+        assert_eq!(hier.run("(g 1)".to_string()).unwrap().text_representation(), "1");
+    }
+}
@@ -0,0 +1,12 @@
+//! Integers are exact and separate from floats.
+
+mod common;
+
+use common::*;
+
+#[test]
+fn integers() {
+    assert_eq!(value("(& (+ 1 2) (/ 7 2) (div 7 2) (% 7 3) (is 3 Integer) (is 3.0 Integer))"), "(3 3.5 3 1 true false)");
+    assert_eq!(value("(& (round 2.5) (round -2.5) (round 2.4) (is (round 3.7) Integer))"), "(3 -3 2 true)");
+    assert_eq!(value("(try (number \"abc\") { error.kind })"), "ValueError");
+}
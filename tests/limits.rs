@@ -0,0 +1,25 @@
+//! Resources used by the code can be limited.
+
+mod common;
+
+use hier::{HierErrorKind, Limit, Limits, Policy};
+use common::*;
+
+#[test]
+fn limits() {
+    for backend in BACKENDS {
+        let mut hier = hier(backend, Policy::default());
+
+        hier.set_limits(Limits { steps: Some(10_000), ..Limits::default() });
+        assert_eq!(hier.run("(repeat { 1 })".to_string()).unwrap_err().kind, HierErrorKind::LIMIT(Limit::STEPS));
+        assert_eq!(hier.run("(repeat 3 { 1 })".to_string()).unwrap().text_representation(), "NULL");
+
+        hier.set_limits(Limits { depth: Some(50), ..Limits::default() });
+        assert_eq!(hier.run("(@f (| n) { (f (+ n 1)) }) (f 0)".to_string()).unwrap_err().kind, HierErrorKind::LIMIT(Limit::DEPTH));
+        assert_eq!(hier.run("(@g (| n) { (if (== n 0) { 0 } { (g (- n 1)) }) }) (g 40)".to_string()).unwrap().text_representation(), "0");
+
+        hier.set_limits(Limits { value_size: Some(1_000), ..Limits::default() });
+        assert_eq!(hier.run("(@s \"a\") (repeat 20 { (=s (+ s s)) })".to_string()).unwrap_err().kind, HierErrorKind::LIMIT(Limit::VALUE_SIZE));
+        assert_eq!(hier.run("(@t \"a\") (repeat 8 { (=t (+ t t)) }) ((+ t) t t t t)".to_string()).unwrap_err().kind, HierErrorKind::LIMIT(Limit::VALUE_SIZE));
+    }
+}
@@ -0,0 +1,13 @@
+//! Continue, break with values and labeled breaks.
+
+mod common;
+
+use common::*;
+
+#[test]
+fn break_and_continue() {
+    assert_eq!(value("(for (& 1 2 3 4 5) { (if (== element 2) { (continue) }) (if (== element 4) { (break (* element 100)) }) })"), "400");
+    assert_eq!(value("(for (& 1 2 3) { (@a element) (for (& 1 2 3) { (if (== (* a element) 6) { (break (& a element) label:\"search\") }) }) } label:\"search\")"), "(2 3)");
+    assert_eq!(value("(repeat { (break \"out\") })"), "out");
+    assert!(error("(break)").contains("Break must be used inside of a loop"));
+}
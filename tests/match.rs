@@ -0,0 +1,13 @@
+//! Match picks the first case whose pattern matches the value.
+
+mod common;
+
+use common::*;
+
+#[test]
+fn match_patterns() {
+    let describe = "(@describe (| x) { (match x 0 { \"zero\" } Integer if { (> x 100) } { \"big\" } Integer { \"integer\" } (first &rest) { rest } (# name) { name } _ { \"other\" }) })";
+
+    assert_eq!(value(&format!("{} (& (describe 0) (describe 500) (describe 5) (describe (& 1 2 3)) (describe (table name:\"Ann\")) (describe true))", describe)), "(zero big integer (2 3) Ann other)");
+    assert_eq!(value("(@x 10) (& (match 1 x { x }) x)"), "(1 10)");
+}
@@ -0,0 +1,21 @@
+//! Functions implemented in Rust, which can capture state of the host.
+
+mod common;
+
+use hier::{Policy, Value};
+use common::*;
+
+#[test]
+fn native_functions() {
+    for backend in BACKENDS {
+        let mut hier = hier(backend, Policy::default());
+        let mut count = 0;
+
+        hier.add_native_function_mut("count".to_string(), -1, move |_environment, arguments| {
+            count += arguments.len() as i64;
+            Ok(Value::INTEGER(count))
+        });
+
+        assert_eq!(hier.run("(count 1 2) (count 3)".to_string()).unwrap().text_representation(), "3");
+    }
+}
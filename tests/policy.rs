@@ -0,0 +1,13 @@
+//! Code can only do what its policy allows.
+
+mod common;
+
+use hier::Policy;
+use common::*;
+
+#[test]
+fn policy() {
+    assert_eq!(value("(try (eval \"(+ 1 2)\") { error.kind })"), "PermissionError");
+    assert_eq!(value("(try (import \"/etc/passwd\") { error.kind })"), "PermissionError");
+    assert_eq!(run_with(Policy { eval: true, ..Policy::default() }, "(eval \"(+ 1 2)\")"), Ok("3".to_string()));
+}
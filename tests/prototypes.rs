@@ -0,0 +1,13 @@
+//! Tables can have prototypes, from which they get fields and methods.
+
+mod common;
+
+use common::*;
+
+#[test]
+fn prototypes() {
+    let animals = "(@Animal (table init:((| self name) { (set self \"name\" name) }) speak:((| self) { (+ self.name \" makes a sound\") }))) (@Dog (table prototype:Animal speak:((| self) { (+ self.name \" barks\") })))";
+
+    assert_eq!(value(&format!("{} (& ((new Animal \"Tom\").speak) ((new Dog \"Rex\").speak))", animals)), "(Tom makes a sound Rex barks)");
+    assert_eq!(value("(@point (new (table) x:1 y:2)) (& point.x point.y)"), "(1 2)");
+}
@@ -0,0 +1,20 @@
+//! Record types declared by the code, which work with is, match and Type.
+
+mod common;
+
+use common::*;
+
+#[test]
+fn records() {
+    assert_eq!(value("(@Point (record x y)) (@p (Point 1 2)) (@q (Point y:5 x:4)) (=q.x 10) (& p q (is p Point) (== p (Point 1 2)))"), "(Point(x:1 y:2) Point(x:10 y:5) true true)");
+    assert_eq!(value("(@Point (record x y)) (& (match (Point 1 2) Point { \"point\" } _ { \"other\" }) (match 5 Point { \"point\" } _ { \"other\" }))"), "(point other)");
+    assert!(error("(@Point (record x y)) (Point 1)").contains("Record Point has 2 fields"));
+}
+
+#[test]
+fn record_types_with_the_same_name_are_different() {
+    let points = "(@A (record x y name:\"Point\")) (@B (record x y name:\"Point\")) (@a (A 1 2))";
+
+    assert_eq!(value(&format!("{} (& (is a A) (is a B) (== a (A 1 2)) (== a (B 1 2)))", points)), "(true false true false)");
+    assert_eq!(value(&format!("{} (match a B {{ \"b\" }} A {{ \"a\" }})", points)), "a");
+}
@@ -0,0 +1,12 @@
+//! Return leaves the function (or the program), even from nested loops.
+
+mod common;
+
+use common::*;
+
+#[test]
+fn return_leaves_functions() {
+    assert_eq!(value("(@find (| list wanted) { (for list { (if (== element wanted) { (return true) }) }) false }) (& (find (& 1 2) 2) (find (& 1 2) 5))"), "(true false)");
+    assert_eq!(value("(@f (| n) { (while { true } { (repeat { (if (> n 3) { (return (* n 10)) }) (=n (+ n 1)) }) }) 0 }) (f 1)"), "40");
+    assert_eq!(value("(return 3) 4"), "3");
+}
@@ -0,0 +1,11 @@
+//! Lists, strings and tables are shared until they are changed, so changes never affect other variables.
+
+mod common;
+
+use common::*;
+
+#[test]
+fn persistent_values() {
+    assert_eq!(value("(@a (& 1 2)) (@b (append a 3)) (& a b)"), "((1 2) (1 2 3))");
+    assert_eq!(value("(@t (table a:1)) (@f (| t) { (=t.a 2) t.a }) (& (f t) t.a)"), "(2 1)");
+}
@@ -0,0 +1,12 @@
+//! Tables can be changed: fields are added, updated and deleted.
+
+mod common;
+
+use common::*;
+
+#[test]
+fn tables() {
+    assert_eq!(value("(@config (table host:\"localhost\" port:80)) (=config.port 8080) (& config.port (has config \"host\") (keys config) (values config))"), "(8080 true (host port) (localhost 8080))");
+    assert_eq!(value("(@t (table a:1 b:2)) (& (keys (delete t \"a\")) (keys t) (merge t (table b:3)).b)"), "((b) (a b) 3)");
+    assert_eq!(value("(@grid (& (& 1 2) (& 3 4))) (=grid[1][0] 9) grid"), "((1 2) (9 4))");
+}
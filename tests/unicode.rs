@@ -0,0 +1,11 @@
+//! Strings are indexed and measured by characters, not bytes.
+
+mod common;
+
+use common::*;
+
+#[test]
+fn unicode() {
+    assert_eq!(value("(@s \"żółw\") (& (length s) s[1] (remove s 0))"), "(4 ó ółw)");
+    assert_eq!(value("(length (graphemes \"e\u{301}👍🏽\"))"), "2");
+}
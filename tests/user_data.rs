@@ -0,0 +1,42 @@
+//! Objects of the host with properties and methods, which the code can use.
+
+mod common;
+
+use std::cell::Cell;
+use std::rc::Rc;
+use hier::{Environment, HierError, Policy, UserData, Value};
+use common::*;
+
+struct Counter {
+    count: Cell<i64>
+}
+
+impl UserData for Counter {
+    fn type_name(&self) -> String {
+        "Counter".to_string()
+    }
+
+    fn get_property(&self, name: &str) -> Option<Value> {
+        if name == "count" { Some(Value::INTEGER(self.count.get())) } else { None }
+    }
+
+    fn has_method(&self, name: &str) -> bool {
+        name == "add"
+    }
+
+    fn call_method(&self, _environment: &mut Environment, _name: &str, arguments: Vec<Value>) -> Result<Value, HierError> {
+        self.count.set(self.count.get() + arguments.len() as i64);
+        Ok(Value::NULL)
+    }
+}
+
+#[test]
+fn host_objects() {
+    for backend in BACKENDS {
+        let mut hier = hier(backend, Policy::default());
+        hier.add_variable("counter".to_string(), Value::USER_DATA(Rc::new(Counter { count: Cell::new(0) })));
+
+        assert_eq!(hier.run("(counter.add 1 2) counter.count".to_string()).unwrap().text_representation(), "2");
+        assert_eq!(hier.run("(is counter \"Counter\")".to_string()).unwrap().text_representation(), "true");
+    }
+}